@@ -0,0 +1,251 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use mut_elems::*;
+
+/// Length of the slice indexed into.
+const LEN: usize = 4096;
+
+/// Length of a slice too long for the bitset overlap check.
+const LONG_LEN: usize = 1 << 16;
+
+/// Strictly ascending indices spread across the slice.
+fn ascending<const N: usize>() -> [usize; N] {
+    std::array::from_fn(|i| i * (LEN / N))
+}
+
+fn bench_sorted_n<const N: usize>(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sorted");
+    let mut v = vec![0u64; LEN];
+    let indices = ascending::<N>();
+
+    group.bench_with_input(BenchmarkId::new("mut_elems", N), &indices, |b, ix| {
+        b.iter(|| {
+            let es = v.mut_elems(black_box(ix)).unwrap();
+            *es[N - 1] += 1;
+        })
+    });
+    group.bench_with_input(
+        BenchmarkId::new("mut_elems_sorted", N),
+        &indices,
+        |b, ix| {
+            b.iter(|| {
+                let es = v.mut_elems_sorted(black_box(ix)).unwrap();
+                *es[N - 1] += 1;
+            })
+        },
+    );
+    group.finish();
+}
+
+fn bench_sorted(c: &mut Criterion) {
+    bench_sorted_n::<8>(c);
+    bench_sorted_n::<16>(c);
+    bench_sorted_n::<32>(c);
+    bench_sorted_n::<64>(c);
+}
+
+/// Distinct indices spread across a slice of length `len`,
+/// in a scrambled order so that sorting has work to do.
+fn scattered<const N: usize>(len: usize) -> [usize; N] {
+    std::array::from_fn(|i| (i * 7 % N) * (len / N))
+}
+
+// Compare overlap-checking strategies across `N`: the
+// pairwise scan up to `LINEAR_SCAN_LIMIT`, and beyond that
+// the bitset (short target) or sort (long target). `LINEAR_SCAN_LIMIT` is
+// the point where the pairwise scan stops beating the
+// bitset; rerun these with it raised to check. The "dense"
+// target is long, but its indices span only `LEN`
+// positions, so are checked with a bitset too.
+fn bench_overlap_n<const N: usize>(c: &mut Criterion) {
+    let mut group = c.benchmark_group("overlap");
+    for (name, len, span) in [
+        ("short", LEN, LEN),
+        ("long", LONG_LEN, LONG_LEN),
+        ("dense", LONG_LEN, LEN),
+    ] {
+        let mut v = vec![0u64; len];
+        let indices = scattered::<N>(span).map(|ix| ix + (len - span) / 2);
+        group.bench_with_input(BenchmarkId::new(name, N), &indices, |b, ix| {
+            b.iter(|| {
+                let es = v.mut_elems(black_box(ix)).unwrap();
+                *es[N - 1] += 1;
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_overlap(c: &mut Criterion) {
+    bench_overlap_n::<2>(c);
+    bench_overlap_n::<3>(c);
+    bench_overlap_n::<4>(c);
+    bench_overlap_n::<6>(c);
+    bench_overlap_n::<8>(c);
+    bench_overlap_n::<12>(c);
+    bench_overlap_n::<16>(c);
+    bench_overlap_n::<24>(c);
+    bench_overlap_n::<32>(c);
+}
+
+// Hand-written `split_at_mut` chains for ascending indices,
+// as a caller would write them without this crate.
+fn split_at_mut_2(v: &mut [u64], [i, j]: [usize; 2]) -> [&mut u64; 2] {
+    let (a, b) = v.split_at_mut(j);
+    [&mut a[i], &mut b[0]]
+}
+
+fn split_at_mut_3(v: &mut [u64], [i, j, k]: [usize; 3]) -> [&mut u64; 3] {
+    let (a, rest) = v.split_at_mut(j);
+    let (b, c) = rest.split_at_mut(k - j);
+    [&mut a[i], &mut b[0], &mut c[0]]
+}
+
+fn split_at_mut_4(v: &mut [u64], [i, j, k, l]: [usize; 4]) -> [&mut u64; 4] {
+    let (a, rest) = v.split_at_mut(j);
+    let (b, rest) = rest.split_at_mut(k - j);
+    let (c, d) = rest.split_at_mut(l - k);
+    [&mut a[i], &mut b[0], &mut c[0], &mut d[0]]
+}
+
+// Compare `mut_elems` and `mut_elems_sorted` against
+// hand-written `split_at_mut` chains and the standard
+// library's `get_disjoint_mut`, for a slice small enough to
+// sit in cache and one too long for the bitset check.
+// `mut_elems` runs within a nanosecond of `get_disjoint_mut`
+// up to `N = 4`, and ahead of the `split_at_mut` chains,
+// which check bounds again at every split.
+fn bench_split_n<const N: usize>(
+    c: &mut Criterion,
+    split: fn(&mut [u64], [usize; N]) -> [&mut u64; N],
+) {
+    let mut group = c.benchmark_group("split");
+    for (name, len) in [("small", 16), ("large", LONG_LEN)] {
+        let mut v = vec![0u64; len];
+        let indices: [usize; N] = std::array::from_fn(|i| i * (len / N) + 1);
+        let id = |f: &str| BenchmarkId::new(format!("{f}/{name}"), N);
+
+        group.bench_with_input(id("mut_elems"), &indices, |b, ix| {
+            b.iter(|| {
+                let es = v.mut_elems(black_box(ix)).unwrap();
+                *es[N - 1] += 1;
+            })
+        });
+        group.bench_with_input(id("mut_elems_sorted"), &indices, |b, ix| {
+            b.iter(|| {
+                let es = v.mut_elems_sorted(black_box(ix)).unwrap();
+                *es[N - 1] += 1;
+            })
+        });
+        group.bench_with_input(id("split_at_mut"), &indices, |b, ix| {
+            b.iter(|| {
+                let es = split(&mut v, *black_box(ix));
+                *es[N - 1] += 1;
+            })
+        });
+        group.bench_with_input(id("std_get_disjoint_mut"), &indices, |b, ix| {
+            b.iter(|| {
+                let es = <[u64]>::get_disjoint_mut(&mut v, *black_box(ix)).unwrap();
+                *es[N - 1] += 1;
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_split(c: &mut Criterion) {
+    bench_split_n::<2>(c, split_at_mut_2);
+    bench_split_n::<3>(c, split_at_mut_3);
+    bench_split_n::<4>(c, split_at_mut_4);
+}
+
+fn bench_sort_net_n<const N: usize>(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sort_net");
+    let a: [u64; N] = scattered::<N>(LEN).map(|i| i as u64);
+
+    group.bench_with_input(BenchmarkId::new("sort_net", N), &a, |b, a| {
+        b.iter(|| {
+            let mut a = black_box(*a);
+            a.sort_net();
+            a
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("sort_unstable", N), &a, |b, a| {
+        b.iter(|| {
+            let mut a = black_box(*a);
+            a.sort_unstable();
+            a
+        })
+    });
+    group.finish();
+}
+
+fn bench_sort_net(c: &mut Criterion) {
+    bench_sort_net_n::<4>(c);
+    bench_sort_net_n::<6>(c);
+    bench_sort_net_n::<8>(c);
+}
+
+// A large gather from a large byte buffer. Compare the
+// bounds check alone with the whole gather: the gather is
+// dominated by sorting the indices for the overlap check,
+// so vectorizing the bounds check would not pay.
+#[cfg(feature = "alloc")]
+fn bench_gather(c: &mut Criterion) {
+    const BUF_LEN: usize = 1 << 20;
+    const GATHER: usize = 10_000;
+    let mut group = c.benchmark_group("gather");
+    let mut v = vec![0u8; BUF_LEN];
+    let indices: Vec<usize> = (0..GATHER).map(|i| i * 7919 % BUF_LEN).collect();
+
+    group.bench_function("mut_elems_slice/10000", |b| {
+        b.iter(|| {
+            let mut es = v.mut_elems_slice(black_box(&indices)).unwrap();
+            *es[GATHER - 1] += 1;
+        })
+    });
+    group.bench_function("bounds/10000", |b| {
+        b.iter(|| check_bounds(black_box(&indices), BUF_LEN).unwrap())
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "alloc"))]
+fn bench_gather(_: &mut Criterion) {}
+
+#[cfg(feature = "smallvec")]
+fn bench_smallvec(c: &mut Criterion) {
+    let mut group = c.benchmark_group("as_mut_elems");
+    let mut v = [0u64; 8];
+
+    group.bench_function("vec/8", |b| {
+        b.iter(|| {
+            let mut es = black_box(&mut v).as_mut_elems_vec();
+            *es[7] += 1;
+            black_box(es);
+        })
+    });
+    group.bench_function("smallvec/8", |b| {
+        b.iter(|| {
+            let mut es = black_box(&mut v).as_mut_elems_smallvec::<8>();
+            *es[7] += 1;
+            black_box(es);
+        })
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "smallvec"))]
+fn bench_smallvec(_: &mut Criterion) {}
+
+criterion_group!(
+    benches,
+    bench_sorted,
+    bench_overlap,
+    bench_split,
+    bench_sort_net,
+    bench_gather,
+    bench_smallvec
+);
+criterion_main!(benches);
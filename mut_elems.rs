@@ -1,4 +1,5 @@
 #![doc(html_root_url = "https://docs.rs/mut-elems/0.2.0")]
+#![no_std]
 
 /*!
 
@@ -24,19 +25,102 @@ let es: [&mut u8; 4] = a.as_mut_elems();
 *es[3] = 7;
 assert_eq!([1, 5, 3, 7], a);
 
+# #[cfg(feature = "alloc")] {
 let mut aref: &mut [u8] = a.as_mut();
 let mut es: Vec<&mut u8> = aref.as_mut_elems_vec();
 *es[1] = 5;
 *es[3] = 7;
 assert_eq!([1, 5, 3, 7], a);
+# }
 ```
 
+# Features
+
+This crate is `no_std`. Everything not gated on a feature
+works without an allocator. The features are:
+
+* `alloc` (on by default): the parts of the API that need
+  an allocator, such as [AsMutElemsVecExt] and the methods
+  returning `Vec`.
+* `std` (on by default, implies `alloc`): [MutValuesExt]
+  for `HashMap`.
+* `rayon` (implies `std`): [ParMutElemsExt], for
+  processing selected elements in parallel with `rayon`.
+* `smallvec` (implies `alloc`):
+  [AsMutElemsVecExt::as_mut_elems_smallvec].
+* `ndarray` (implies `alloc`): [MutElemsViewExt] for
+  one-dimensional `ndarray` arrays and views.
+* `trace`: logs, with `log::trace!`, which strategy each
+  index check uses, for performance debugging.
+* `arrayvec`: [MutElemsExt::mut_elems_arrayvec], which
+  needs no allocator.
+* `serde`: derives `Serialize` and `Deserialize` for
+  [MutElemsError] and [MutElemsErrorKind].
+
 */
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, VecDeque},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "alloc")]
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut, Index, IndexMut, Range};
+use core::pin::Pin;
+
 use thiserror::Error;
 
-/// Failure cases for [MutElemsExt::mut_elems].
+/// Log the strategy chosen for an index check, when the
+/// `trace` feature is enabled.
+#[cfg(feature = "trace")]
+macro_rules! trace_strategy {
+    ($strategy:literal, $n:expr, $length:expr) => {
+        log::trace!(
+            "checking {} indices against length {}: {}",
+            $n,
+            $length,
+            $strategy,
+        )
+    };
+}
+
+#[cfg(not(feature = "trace"))]
+macro_rules! trace_strategy {
+    ($strategy:literal, $n:expr, $length:expr) => {
+        let _ = ($n, $length);
+    };
+}
+
+/// Failure cases for [MutElemsExt::mut_elems] and the other
+/// methods of this crate.
+///
+/// New variants may be added as the crate grows. To branch on
+/// the general category of an error without matching each
+/// variant, use [MutElemsError::kind].
+///
+/// With the `serde` feature, errors serialize in serde's
+/// default externally tagged form, keyed by variant name with
+/// the fields named as here: for example
+/// `{"IndexBound":{"position":1,"index":3,"length":3}}` in
+/// JSON. These names are part of the crate's stable API, and
+/// will not change without a major version bump.
 #[derive(Error, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum MutElemsError {
     /// There is a repeated index in the provided indices.
     #[error("indices {first} and {second} are both {index}")]
@@ -58,174 +142,7766 @@ pub enum MutElemsError {
         /// Number of elements in target: should be greater than index.
         length: usize,
     },
+    /// There are repeated indices in the provided indices.
+    /// Returned by [MutElemsExt::mut_elems_report_all].
+    #[cfg(feature = "alloc")]
+    #[error("{} indices are repeated", .groups.len())]
+    IndicesOverlapAll {
+        /// For each repeated index, in order of first
+        /// appearance: the value of the index and every
+        /// position at which it appears, in ascending order.
+        groups: Vec<(usize, Vec<usize>)>,
+    },
+    /// There is a repeated key in the provided keys.
+    /// Returned by [MutValuesExt::mut_values].
+    #[error("keys {first} and {second} are equal")]
+    KeysOverlap {
+        /// First position of repeated key in keys.
+        first: usize,
+        /// Second position of repeated key in keys.
+        second: usize,
+    },
+    /// Two of the provided ranges overlap.
+    /// Returned by [MutElemsExt::mut_ranges] and
+    /// [MutElemsExt::windows_disjoint_mut].
+    #[error("ranges {first} and {second} overlap")]
+    RangesOverlap {
+        /// Position of the earlier overlapping range in ranges.
+        first: usize,
+        /// Position of the later overlapping range in ranges.
+        second: usize,
+    },
+    /// A provided pair of coordinates is out of bounds.
+    /// Returned by [MutPixelsExt::mut_pixels].
+    #[error("coordinates {position} are ({row}, {col}), but target is {rows}x{cols}")]
+    Index2DBound {
+        /// Position of out-of-bounds coordinates in coords.
+        position: usize,
+        /// Row of out-of-bounds coordinates.
+        row: usize,
+        /// Column of out-of-bounds coordinates.
+        col: usize,
+        /// Number of rows in target: should be greater than row.
+        rows: usize,
+        /// Number of columns in target: should be greater than col.
+        cols: usize,
+    },
+    /// A provided range is reversed or out of bounds.
+    /// Returned by [MutElemsExt::mut_ranges] and
+    /// [MutElemsExt::windows_disjoint_mut].
+    #[error("range {position} is {start}..{end}, but target length is {length}")]
+    RangeBound {
+        /// Position of bad range in ranges.
+        position: usize,
+        /// Start of bad range.
+        start: usize,
+        /// End of bad range.
+        end: usize,
+        /// Number of elements in target: should be at least end.
+        length: usize,
+    },
+    /// The provided indices are not in order.
+    /// Returned by [MutElemsExt::mut_elems_sorted].
+    #[error("index {position} is out of order")]
+    IndicesUnsorted {
+        /// Position of the first index that is out of order
+        /// with respect to the index before it.
+        position: usize,
+    },
+    /// A provided index does not fit in a `usize`.
+    /// Returned by [MutElemsExt::mut_elems_generic].
+    #[error("index {position} does not fit in a usize")]
+    IndexConversion {
+        /// Position of unrepresentable index in indices.
+        position: usize,
+    },
+    /// A provided key is not present in the map.
+    /// Returned by [MutValuesExt::mut_values].
+    #[error("key {position} is not in the map")]
+    MissingKey {
+        /// Position of missing key in keys.
+        position: usize,
+    },
+    /// Another error, together with the full list of indices
+    /// that caused it.
+    /// Returned by [MutElemsExt::mut_elems_verbose].
+    #[cfg(feature = "alloc")]
+    #[error("{error} (indices {indices:?})")]
+    WithIndices {
+        /// The underlying error.
+        error: Box<MutElemsError>,
+        /// The indices passed in.
+        indices: Box<[usize]>,
+    },
+    /// More indices were provided than the result can hold.
+    /// Returned by [MutElemsExt::mut_elems_arrayvec].
+    #[error("{count} indices were given, but at most {capacity} are allowed")]
+    CapacityExceeded {
+        /// Number of indices provided.
+        count: usize,
+        /// Maximum number of indices.
+        capacity: usize,
+    },
+    /// The target, or an argument that must match it, does
+    /// not have the required length.
+    /// Returned by [MutElemsExt::as_mut_elems_array],
+    /// [MutElemsExt::mut_elems_masked],
+    /// [MutElemsExt::mut_first], [MutElemsExt::mut_last],
+    /// [MutElemsExt::mut_k_smallest] and
+    /// [MutElemsExt::mut_k_largest].
+    #[error("length is {actual}, but {expected} was required")]
+    LengthMismatch {
+        /// Required length.
+        expected: usize,
+        /// Actual length of the target or argument.
+        actual: usize,
+    },
+    /// A provided permutation is not a permutation of its
+    /// positions.
+    /// Returned by [MutElemsExt::permute_elems].
+    #[error("permutation entry {position} is out of range or repeated")]
+    InvalidPermutation {
+        /// Position of the first entry of the permutation that
+        /// is out of range or repeats an earlier entry.
+        position: usize,
+    },
+    /// A provided byte index is not on a `char` boundary.
+    /// Returned by [MutBytesExt::mut_bytes_at].
+    #[error("index {position} is {index}, which is not a char boundary")]
+    NotCharBoundary {
+        /// Position of the bad index in indices.
+        position: usize,
+        /// Value of the bad index.
+        index: usize,
+    },
 }
 use MutElemsError::*;
 
-pub trait MutElemsExt<T> {
-    /// Return mutable references to elements of `self`
-    /// at each of the index positions given by `indices`.
+/// General category of a [MutElemsError], as returned by
+/// [MutElemsError::kind].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum MutElemsErrorKind {
+    /// Two indices, keys or ranges refer to the same element.
+    Overlap,
+    /// An index or range is outside the target.
+    OutOfBounds,
+    /// Indices are not in the required order.
+    Unsorted,
+    /// An index could not be converted to a `usize`.
+    Conversion,
+    /// A key is not present in the target map.
+    MissingKey,
+    /// The target or the indices have the wrong length.
+    Length,
+    /// A permutation is invalid.
+    Permutation,
+    /// A byte index is inside a UTF-8 encoded `char`.
+    CharBoundary,
+}
+
+impl MutElemsError {
+    /// Return the general category of this error.
+    pub fn kind(&self) -> MutElemsErrorKind {
+        match self {
+            IndicesOverlap { .. } | KeysOverlap { .. } | RangesOverlap { .. } => {
+                MutElemsErrorKind::Overlap
+            }
+            #[cfg(feature = "alloc")]
+            IndicesOverlapAll { .. } => MutElemsErrorKind::Overlap,
+            IndexBound { .. } | Index2DBound { .. } | RangeBound { .. } => {
+                MutElemsErrorKind::OutOfBounds
+            }
+            IndicesUnsorted { .. } => MutElemsErrorKind::Unsorted,
+            IndexConversion { .. } => MutElemsErrorKind::Conversion,
+            MissingKey { .. } => MutElemsErrorKind::MissingKey,
+            LengthMismatch { .. } | CapacityExceeded { .. } => MutElemsErrorKind::Length,
+            InvalidPermutation { .. } => MutElemsErrorKind::Permutation,
+            NotCharBoundary { .. } => MutElemsErrorKind::CharBoundary,
+            #[cfg(feature = "alloc")]
+            WithIndices { error, .. } => error.kind(),
+        }
+    }
+
+    /// Return a short code for the variant of this error, for
+    /// structured logging. Unlike the `Display` messages,
+    /// which are for people and may be reworded, the codes
+    /// will not change. A [MutElemsError::WithIndices] has the
+    /// code of the error it wraps.
     ///
-    /// All indices must be unique, as Rust does not allow
-    /// multiple mutable references to the same object.
+    /// # Examples
     ///
-    /// # Errors
+    /// ```
+    /// use mut_elems::*;
     ///
-    /// Will return an error if any of the indices are out of bounds,
-    /// or if any pair of indices is identical.
-    fn mut_elems<'a, const N: usize>(
-        &'a mut self,
-        indices: &[usize; N],
-    ) -> Result<[&'a mut T; N], MutElemsError>;
+    /// let mut a = [1u8, 2, 3];
+    /// let err = a.mut_elems(&[0, 3]).unwrap_err();
+    /// assert_eq!("index 1 is 3, but target length is 3", err.to_string());
+    /// assert_eq!("E_BOUNDS", err.code());
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            IndicesOverlap { .. } => "E_OVERLAP",
+            IndexBound { .. } => "E_BOUNDS",
+            #[cfg(feature = "alloc")]
+            IndicesOverlapAll { .. } => "E_OVERLAP_ALL",
+            KeysOverlap { .. } => "E_KEYS_OVERLAP",
+            RangesOverlap { .. } => "E_RANGES_OVERLAP",
+            RangeBound { .. } => "E_RANGE_BOUNDS",
+            Index2DBound { .. } => "E_BOUNDS_2D",
+            IndicesUnsorted { .. } => "E_UNSORTED",
+            IndexConversion { .. } => "E_CONVERSION",
+            MissingKey { .. } => "E_MISSING_KEY",
+            #[cfg(feature = "alloc")]
+            WithIndices { error, .. } => error.code(),
+            CapacityExceeded { .. } => "E_CAPACITY",
+            LengthMismatch { .. } => "E_LENGTH",
+            InvalidPermutation { .. } => "E_PERMUTATION",
+            NotCharBoundary { .. } => "E_CHAR_BOUNDARY",
+        }
+    }
 }
 
-pub trait AsMutElemsExt<const N: usize, T> {
-    /// Return an array of mutable references to each
-    /// of the elements of the input array.
-    fn as_mut_elems(&mut self) -> [&mut T; N];
+/// A [MutElemsError] together with the indices that caused
+/// it, for reporting to users.
+/// Returned by [MutElemsExt::mut_elems_diagnostic].
+#[cfg(feature = "alloc")]
+#[derive(Error, Debug, Clone, PartialEq, Eq, Hash)]
+#[error("{error} (indices {indices:?})")]
+pub struct MutElemsDiagnostic {
+    /// The underlying error.
+    #[source]
+    pub error: MutElemsError,
+    /// The indices passed in, in their original order.
+    pub indices: Vec<usize>,
 }
 
-pub trait AsMutElemsVecExt<T> {
-    /// Return a `Vec` of mutable references to each
-    /// of the elements of the input `Vec`.
-    fn as_mut_elems_vec(&mut self) -> Vec<&mut T>;
+#[cfg(feature = "alloc")]
+impl MutElemsDiagnostic {
+    /// Return a copy of the indices in ascending order, in
+    /// which any repeated indices are adjacent.
+    pub fn sorted_indices(&self) -> Vec<usize> {
+        let mut sorted = self.indices.clone();
+        sorted.sort_unstable();
+        sorted
+    }
 }
 
-impl<T> MutElemsExt<T> for [T] {
-    fn mut_elems<'a, const N: usize>(
-        &'a mut self,
-        indices: &[usize; N],
-    ) -> Result<[&'a mut T; N], MutElemsError> {
-        // Index checking. 0, 1, 2 are special-cased for
-        // performance, in particular since 2 may be commonly
-        // used.
-        match N {
-            0 | 1 => (),
-            2 => {
-                if indices[0] == indices[1] {
-                    return Err(IndicesOverlap {
-                        first: indices[0],
-                        second: indices[1],
-                        index: indices[0],
-                    });
-                }
-            }
-            _ => {
-                use std::collections::HashMap;
-
-                let mut seen: HashMap<usize, usize> = HashMap::with_capacity(indices.len());
-
-                for (i, ix) in indices.iter().enumerate() {
-                    if seen.contains_key(ix) {
-                        let j = seen[ix];
-                        return Err(IndicesOverlap {
-                            first: j,
-                            second: i,
-                            index: *ix,
-                        });
-                    }
-                    seen.insert(*ix, i);
+#[cfg(feature = "alloc")]
+impl From<MutElemsDiagnostic> for MutElemsError {
+    /// Convert to [MutElemsError::WithIndices].
+    fn from(diagnostic: MutElemsDiagnostic) -> Self {
+        WithIndices {
+            error: Box::new(diagnostic.error),
+            indices: diagnostic.indices.into_boxed_slice(),
+        }
+    }
+}
+
+/// Integer types usable as indices with
+/// [MutElemsExt::mut_elems_generic].
+pub trait ElemIndex: Copy {
+    /// Convert to a `usize` index, or `None` if the value
+    /// does not fit.
+    fn to_usize(self) -> Option<usize>;
+}
+
+macro_rules! elem_index_impls {
+    ($($t:ty),*) => {
+        $(
+            impl ElemIndex for $t {
+                fn to_usize(self) -> Option<usize> {
+                    usize::try_from(self).ok()
                 }
             }
-        }
+        )*
+    };
+}
 
-        // Index bounds checking.
-        let nself = self.len();
-        for (i, ix) in indices.iter().enumerate() {
-            if *ix >= nself {
-                return Err(IndexBound {
-                    position: i,
-                    index: *ix,
-                    length: nself,
-                });
+elem_index_impls!(usize, u8, u16, u32, u64);
+
+/// Check a buffer of `(index, position)` pairs for a repeated
+/// index. The buffer is sorted in place. On overlap, reports
+/// the same pair a left-to-right scan of the indices would
+/// find first: the earliest position that repeats an index,
+/// together with that index's first position.
+#[inline]
+fn find_overlap(pairs: &mut [(usize, usize)]) -> Result<(), MutElemsError> {
+    pairs.sort_unstable();
+    let mut overlap: Option<(usize, usize, usize)> = None;
+    for w in pairs.windows(2) {
+        let ((ix0, p0), (ix1, p1)) = (w[0], w[1]);
+        if ix0 == ix1 {
+            match overlap {
+                // Only the first repeat of an index matters;
+                // later repeats have later second positions.
+                Some((_, _, ix)) if ix == ix1 => (),
+                Some((_, second, _)) if second < p1 => (),
+                _ => overlap = Some((p0, p1, ix1)),
             }
         }
+    }
+    match overlap {
+        Some((first, second, index)) => Err(IndicesOverlap {
+            first,
+            second,
+            index,
+        }),
+        None => Ok(()),
+    }
+}
 
-        // Safety: Indices have been checked for inequality, so
-        // they must indicate unique locations.  Bounds checking
-        // has already been done, so we can bypass checking the
-        // indices.  `from_fn()` guarantees that `i` is
-        // in-bounds, so we can bypass checking that.
-        Ok(std::array::from_fn(|i| unsafe {
-            &mut *(self.get_unchecked_mut(*indices.get_unchecked(i)) as *mut T)
-        }))
+/// Largest count of indices for which overlap checking
+/// compares every pair of indices rather than sorting or
+/// using a bitset. The `overlap` benches in
+/// `benches/mut_elems.rs` show the pairwise scan beating
+/// the bitset only up to about 4 indices, though it beats
+/// sorting on long targets well beyond that.
+const LINEAR_SCAN_LIMIT: usize = 4;
+
+/// Largest target length for which overlap checking marks
+/// indices in a bitset of target positions rather than
+/// sorting the indices.
+const BITSET_LIMIT: usize = 4096;
+
+/// Largest runtime count of indices for which
+/// [check_indices_slice_in] sorts on the stack rather than
+/// allocating.
+const STACK_PAIRS: usize = 32;
+
+/// Find every index repeated in `indices`, as described
+/// for [MutElemsError::IndicesOverlapAll].
+#[cfg(feature = "alloc")]
+fn overlap_groups(indices: &[usize]) -> Vec<(usize, Vec<usize>)> {
+    let mut pairs: Vec<(usize, usize)> = indices.iter().copied().zip(0..).collect();
+    pairs.sort_unstable();
+    let mut groups: Vec<(usize, Vec<usize>)> = pairs
+        .chunk_by(|(ix0, _), (ix1, _)| ix0 == ix1)
+        .filter(|group| group.len() > 1)
+        .map(|group| (group[0].0, group.iter().map(|&(_, p)| p).collect()))
+        .collect();
+    groups.sort_unstable_by_key(|(_, positions)| positions[0]);
+    groups
+}
+
+// The `check_indices` functions check `indices` for both
+// out-of-bounds and repeated indices, making a single pass
+// over `indices` where they can. The error returned is for
+// the earliest position in `indices` holding an index that
+// is either out of bounds or a repeat of an earlier index.
+// No position can be both, since the first occurrence of a
+// repeated out-of-bounds index is itself reported first.
+//
+// Pointer offsets are only ever taken for indices that have
+// passed such a check, so are less than the length of a live
+// slice. A slice spans at most `isize::MAX` bytes, so the
+// offset in bytes cannot overflow however large the index.
+//
+// None of these checks hash: a pairwise scan, a bitset or a
+// sort is faster for `usize` indices than any hash set, so
+// there is no hasher to choose.
+//
+// These checks are `#[inline]`, and build errors only once
+// they are known to have occurred, so that the optimizer can
+// see that validated calls cannot panic: see
+// `tests/no-panic.rs`.
+
+/// Check `indices` by comparing each index with `length`
+/// and with every earlier index.
+#[inline]
+fn check_indices_linear(indices: &[usize], length: usize) -> Result<(), MutElemsError> {
+    for (second, &index) in indices.iter().enumerate() {
+        if index >= length {
+            return Err(IndexBound {
+                position: second,
+                index,
+                length,
+            });
+        }
+        if let Some(first) = indices.iter().take(second).position(|&ix| ix == index) {
+            return Err(IndicesOverlap {
+                first,
+                second,
+                index,
+            });
+        }
     }
+    Ok(())
 }
 
-impl<const N: usize, T> AsMutElemsExt<N, T> for [T; N] {
-    fn as_mut_elems(&mut self) -> [&mut T; N] {
-        // Safety: `from_fn()` guarantees that indices `i`
-        // are in-bounds and unique.
-        std::array::from_fn(|i| unsafe { &mut *(self.get_unchecked_mut(i) as *mut T) })
+/// Check `indices` using a bitset of [BITSET_LIMIT] bits
+/// for the positions from `low`. Every index must be at
+/// least `low`, and every index less than `length` must be
+/// less than `low + BITSET_LIMIT`.
+#[inline]
+fn check_indices_bitset(indices: &[usize], length: usize, low: usize) -> Result<(), MutElemsError> {
+    let mut bits = [0u64; BITSET_LIMIT / 64];
+    for (second, &index) in indices.iter().enumerate() {
+        if index >= length {
+            return Err(IndexBound {
+                position: second,
+                index,
+                length,
+            });
+        }
+        debug_assert!(index >= low && index - low < BITSET_LIMIT);
+        // The remainder does nothing but show the optimizer
+        // that the offset is in range.
+        let offset = (index - low) % BITSET_LIMIT;
+        let (word, bit) = (offset / 64, 1 << (offset % 64));
+        if bits[word] & bit != 0 {
+            // The bit is set, so an earlier index matches.
+            let first = indices[..second]
+                .iter()
+                .position(|&ix| ix == index)
+                .unwrap_or(0);
+            return Err(IndicesOverlap {
+                first,
+                second,
+                index,
+            });
+        }
+        bits[word] |= bit;
     }
+    Ok(())
 }
 
-impl<T, V> AsMutElemsVecExt<T> for V where V: AsMut<[T]> {
-    fn as_mut_elems_vec(&mut self) -> Vec<&mut T> {
-        // Safety: iteration guarantees that elements
-        // are in-bounds and unique.
-        self.as_mut().iter_mut()
-            .map(|r| unsafe { &mut *(r as *mut T) })
-            .collect()
+/// Check `indices` by sorting `(index, position)` pairs in
+/// `buf`, which must be at least as long as `indices`. The
+/// bounds are checked while filling `buf`; only the indices
+/// before the first out-of-bounds one need checking for
+/// overlap.
+#[inline]
+fn check_indices_sort(
+    indices: &[usize],
+    length: usize,
+    buf: &mut [(usize, usize)],
+) -> Result<(), MutElemsError> {
+    let mut bound = None;
+    let mut n = 0;
+    for (pair, &index) in buf.iter_mut().zip(indices) {
+        if index >= length {
+            bound = Some(index);
+            break;
+        }
+        *pair = (index, n);
+        n += 1;
+    }
+    find_overlap(&mut buf[..n])?;
+    match bound {
+        Some(index) => Err(IndexBound {
+            position: n,
+            index,
+            length,
+        }),
+        None => Ok(()),
     }
 }
 
-#[test]
-fn test_mut_elems() {
-    let mut test_array = [1u8, 2, 3, 4];
+/// Return the lowest position of a [BITSET_LIMIT]-bit
+/// window into the target that [check_indices_bitset] can
+/// check `indices` with, if there is one. On a short target
+/// this is 0. On a long target every index must be in bounds
+/// and all must lie within [BITSET_LIMIT] positions of each
+/// other; finding that out is cheap next to sorting.
+///
+/// If the span of the indices is shorter than their count,
+/// some index must repeat; the bitset then finds the first
+/// repeat without sorting.
+#[inline]
+fn bitset_low(indices: &[usize], length: usize) -> Option<usize> {
+    if length <= BITSET_LIMIT {
+        return Some(0);
+    }
+    let mut low = usize::MAX;
+    let mut high = 0;
+    for &index in indices {
+        low = low.min(index);
+        high = high.max(index);
+    }
+    (high < length && high.saturating_sub(low) < BITSET_LIMIT).then_some(low)
+}
 
-    assert_eq!([&1], test_array.mut_elems(&[0]).unwrap());
-    assert_eq!([&4], test_array.mut_elems(&[3]).unwrap());
-    assert_eq!([&2, &3], test_array.mut_elems(&[1, 2]).unwrap());
-    assert_eq!([&1, &3, &4], test_array.mut_elems(&[0, 2, 3]).unwrap());
+/// Check `indices` against a target of length `length`,
+/// choosing a strategy by the count of indices, the target
+/// length and, on long targets, the span of the indices.
+fn check_indices_in<const N: usize>(
+    indices: &[usize; N],
+    length: usize,
+) -> Result<(), MutElemsError> {
+    if N <= LINEAR_SCAN_LIMIT {
+        trace_strategy!("linear", N, length);
+        check_indices_linear(indices, length)
+    } else if let Some(low) = bitset_low(indices, length) {
+        trace_strategy!("bitset", N, length);
+        check_indices_bitset(indices, length, low)
+    } else {
+        trace_strategy!("sort", N, length);
+        let mut buf = [(0, 0); N];
+        check_indices_sort(indices, length, &mut buf)
+    }
+}
 
-    match test_array.mut_elems(&[4]) {
-        Err(MutElemsError::IndexBound {
-            position,
-            index,
-            length,
-        }) => {
-            assert_eq!(position, 0);
-            assert_eq!(index, 4);
-            assert_eq!(length, 4);
+/// Check `indices`, as with [check_indices_in] but for a
+/// runtime count of indices. Without an allocator, long
+/// sparse index lists on long targets fall back to the
+/// linear scan.
+fn check_indices_slice_in(indices: &[usize], length: usize) -> Result<(), MutElemsError> {
+    let n = indices.len();
+    if n <= LINEAR_SCAN_LIMIT {
+        trace_strategy!("linear", n, length);
+        return check_indices_linear(indices, length);
+    }
+    if let Some(low) = bitset_low(indices, length) {
+        trace_strategy!("bitset", n, length);
+        return check_indices_bitset(indices, length, low);
+    }
+    match n {
+        n if n <= STACK_PAIRS => {
+            trace_strategy!("sort on stack", n, length);
+            let mut buf = [(0, 0); STACK_PAIRS];
+            check_indices_sort(indices, length, &mut buf)
+        }
+        #[cfg(feature = "alloc")]
+        n => {
+            trace_strategy!("sort on heap", n, length);
+            let mut buf = alloc::vec![(0, 0); n];
+            check_indices_sort(indices, length, &mut buf)
+        }
+        #[cfg(not(feature = "alloc"))]
+        n => {
+            trace_strategy!("linear", n, length);
+            check_indices_linear(indices, length)
         }
-        _ => panic!(),
     }
+}
 
-    match test_array.mut_elems(&[1, 2, 1]) {
-        Err(MutElemsError::IndicesOverlap {
-            first,
-            second,
-            index,
-        }) => {
-            assert_eq!(first, 0);
-            assert_eq!(second, 2);
-            assert_eq!(index, 1);
+/// Check that each of `indices` is less than `length`, as
+/// [MutElemsExt::mut_elems] does, without needing a target.
+///
+/// # Errors
+///
+/// Will return [MutElemsError::IndexBound] for the first
+/// index that is out of bounds.
+///
+/// # Examples
+///
+/// ```
+/// use mut_elems::*;
+///
+/// assert!(check_bounds(&[3, 1], 4).is_ok());
+/// assert!(check_bounds(&[3, 4], 4).is_err());
+/// ```
+#[inline]
+pub fn check_bounds(indices: &[usize], length: usize) -> Result<(), MutElemsError> {
+    for (i, ix) in indices.iter().enumerate() {
+        if *ix >= length {
+            return Err(IndexBound {
+                position: i,
+                index: *ix,
+                length,
+            });
         }
-        _ => panic!(),
     }
+    Ok(())
+}
 
-    let es = test_array.mut_elems(&[1, 3]).unwrap();
-    *es[0] = 5;
-    *es[1] = 7;
-    assert_eq!([1, 5, 3, 7], test_array);
+/// Check that `indices` are distinct, as
+/// [MutElemsExt::mut_elems] does, without needing a target.
+///
+/// # Errors
+///
+/// Will return [MutElemsError::IndicesOverlap] for the first
+/// index that repeats an earlier one.
+///
+/// # Examples
+///
+/// ```
+/// use mut_elems::*;
+///
+/// assert!(check_distinct(&[3, 1, 4]).is_ok());
+/// assert_eq!(
+///     Err(MutElemsError::IndicesOverlap { first: 0, second: 2, index: 3 }),
+///     check_distinct(&[3, 1, 3]),
+/// );
+/// ```
+pub fn check_distinct<const N: usize>(indices: &[usize; N]) -> Result<(), MutElemsError> {
+    // Checking against `usize::MAX` stops at any index equal
+    // to it, so check such indices in full.
+    match check_indices_in(indices, usize::MAX) {
+        Err(IndexBound { .. }) => {
+            let mut pairs: [(usize, usize); N] = core::array::from_fn(|i| (indices[i], i));
+            find_overlap(&mut pairs)
+        }
+        result => result,
+    }
 }
 
-#[test]
-fn test_as_mut_elems() {
-    let mut test_array = [1u8, 2, 3, 4];
-    let es = test_array.as_mut_elems();
-    assert_eq!([&1, &2, &3, &4], es);
-    *es[1] = 5;
-    *es[3] = 7;
-    assert_eq!([1, 5, 3, 7], test_array);
+/// Find the positions of the `K` elements of `elems` that
+/// come first when ordered by `before`, in that order. Ties
+/// go to the lower position. Takes `O(len * K)` time and no
+/// allocation, which suits the small `K` of a const generic.
+///
+/// # Errors
+///
+/// Will return [MutElemsError::LengthMismatch] if there are
+/// fewer than `K` elements.
+fn select_k<const K: usize, T>(
+    elems: &[T],
+    before: impl Fn(&T, &T) -> bool,
+) -> Result<[usize; K], MutElemsError> {
+    if elems.len() < K {
+        return Err(LengthMismatch {
+            expected: K,
+            actual: elems.len(),
+        });
+    }
+    let mut best = [0; K];
+    for (i, e) in elems.iter().enumerate() {
+        // Elements are scanned by position, so an element
+        // goes after any it ties with.
+        let filled = i.min(K);
+        let mut j = filled;
+        while j > 0 && before(e, &elems[best[j - 1]]) {
+            j -= 1;
+        }
+        if j < K {
+            best.copy_within(j..filled.min(K - 1), j + 1);
+            best[j] = i;
+        }
+    }
+    Ok(best)
 }
 
-#[test]
-fn test_as_mut_elems_vec() {
-    let mut test_vec = vec![1u8, 2, 3, 4];
-    let mut es = test_vec.as_mut_elems_vec();
-    assert_eq!(vec![&1, &2, &3, &4], es);
-    *es[1] = 5;
-    *es[3] = 7;
-    assert_eq!(vec![1, 5, 3, 7], test_vec);
+/// Mutable references to several elements of a slice.
+///
+/// The references returned for distinct indices are
+/// independent, even when `T` is zero-sized and so every
+/// element has the same address.
+///
+/// Nothing here reads the elements, so slices and arrays of
+/// `MaybeUninit<T>` work as any other, giving references to
+/// possibly-uninitialized slots. Initialization remains up to
+/// the caller.
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use mut_elems::*;
+///
+/// let mut slots = [const { MaybeUninit::<u8>::uninit() }; 3];
+/// for (e, x) in slots.mut_elems(&[2, 0, 1]).unwrap().into_iter().zip(1..) {
+///     e.write(x);
+/// }
+/// // Safety: Every slot has been written.
+/// let values = slots.map(|e| unsafe { e.assume_init() });
+/// assert_eq!([2, 3, 1], values);
+/// ```
+///
+/// Elements may be of any type a slice can hold, including
+/// boxed trait objects. Method calls on the references
+/// returned dereference through the box.
+///
+/// ```
+/// use mut_elems::*;
+///
+/// trait Component {
+///     fn level(&self) -> u8;
+///     fn raise(&mut self, by: u8);
+/// }
+///
+/// struct Meter(u8);
+///
+/// impl Component for Meter {
+///     fn level(&self) -> u8 {
+///         self.0
+///     }
+///     fn raise(&mut self, by: u8) {
+///         self.0 += by;
+///     }
+/// }
+///
+/// let mut components: Vec<Box<dyn Component>> =
+///     vec![Box::new(Meter(1)), Box::new(Meter(2)), Box::new(Meter(3))];
+/// let [a, b] = components.mut_elems(&[2, 0]).unwrap();
+/// a.raise(b.level());
+/// b.raise(a.level());
+/// assert_eq!([5, 2, 4], [0, 1, 2].map(|i| components[i].level()));
+/// ```
+///
+/// Smart pointers such as `Box`, `Vec` and the `RefMut`
+/// guard of a `RefCell` need no special handling: method
+/// calls dereference through them to the slice. The
+/// references returned borrow the guard mutably, so it
+/// cannot be dropped while they are live.
+///
+/// ```
+/// use std::cell::RefCell;
+/// use mut_elems::*;
+///
+/// let cell = RefCell::new(vec![1u8, 2, 3]);
+/// let mut guard = cell.borrow_mut();
+/// let [a, b] = guard.mut_elems(&[2, 0]).unwrap();
+/// core::mem::swap(a, b);
+/// drop(guard);
+/// assert_eq!(vec![3, 2, 1], *cell.borrow());
+/// ```
+///
+/// ```compile_fail
+/// use std::cell::RefCell;
+/// use mut_elems::*;
+///
+/// let cell = RefCell::new(vec![1u8, 2, 3]);
+/// let mut guard = cell.borrow_mut();
+/// let [a] = guard.mut_elems(&[0]).unwrap();
+/// drop(guard);
+/// *a = 0;
+/// ```
+///
+/// Lock guards such as `MutexGuard` work the same way; see
+/// [MutElemsGuardExt] for a helper that names the borrow of
+/// the guard explicitly.
+pub trait MutElemsExt<T> {
+    /// Return mutable references to elements of `self`
+    /// at each of the index positions given by `indices`.
+    ///
+    /// All indices must be unique, as Rust does not allow
+    /// multiple mutable references to the same object.
+    ///
+    /// The references borrow `self`, so a `Vec` cannot
+    /// reallocate while they are live:
+    ///
+    /// ```compile_fail
+    /// use mut_elems::*;
+    ///
+    /// let mut v = vec![1u8, 2, 3];
+    /// let [a] = v.mut_elems(&[0]).unwrap();
+    /// v.push(4);
+    /// *a = 0;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of bounds,
+    /// or if any pair of indices is identical. The error is
+    /// for the first position in `indices`, scanning left to
+    /// right, that holds either an out-of-bounds index
+    /// ([MutElemsError::IndexBound]) or a repeat of an
+    /// earlier index ([MutElemsError::IndicesOverlap]).
+    /// Every index is checked before any is used, so even
+    /// `usize::MAX` gives a plain [MutElemsError::IndexBound].
+    fn mut_elems<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+
+    /// Return a `Vec` of mutable references to elements of
+    /// `self` at each of the index positions given by
+    /// `indices`. This is [MutElemsExt::mut_elems] for when
+    /// the number of indices is not known at compile time.
+    ///
+    /// The references are `Send` whenever `T` is, so they can
+    /// be handed out one per worker of a scoped thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = vec![1u64, 2, 3, 4, 5];
+    /// let es = v.mut_elems_slice(&[4, 0, 2]).unwrap();
+    /// std::thread::scope(|s| {
+    ///     for e in es {
+    ///         s.spawn(move || *e *= 100);
+    ///     }
+    /// });
+    /// assert_eq!(vec![100, 2, 300, 4, 500], v);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of bounds,
+    /// or if any pair of indices is identical.
+    #[cfg(feature = "alloc")]
+    fn mut_elems_slice<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<Vec<&'a mut T>, MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `indices`. This
+    /// is [MutElemsExt::mut_elems], except that all repeated
+    /// indices are reported rather than just the first.
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::IndicesOverlapAll] if any
+    /// pair of indices is identical, or an error if any of
+    /// the indices are out of bounds.
+    #[cfg(feature = "alloc")]
+    fn mut_elems_report_all<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+
+    /// Return mutable references to elements of `self`
+    /// at each of the index positions given by `indices`.
+    /// This is [MutElemsExt::mut_elems] with the name and
+    /// by-value argument of the standard library's
+    /// `slice::get_disjoint_mut`.
+    ///
+    /// On Rust versions where `[T]` has its own inherent
+    /// `get_disjoint_mut`, method-call syntax on a slice will
+    /// find that one first; write
+    /// `MutElemsExt::get_disjoint_mut(s, indices)` to call
+    /// this one.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of bounds,
+    /// or if any pair of indices is identical.
+    fn get_disjoint_mut<const N: usize>(
+        &mut self,
+        indices: [usize; N],
+    ) -> Result<[&mut T; N], MutElemsError>;
+
+    /// Return mutable references to elements of `self`
+    /// at each of the index positions given by `indices`,
+    /// without checking the indices.
+    ///
+    /// # Safety
+    ///
+    /// Every index must be in `0..self.len()`, and the
+    /// indices must be pairwise distinct. Otherwise the
+    /// behavior is undefined.
+    unsafe fn mut_elems_unchecked<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> [&'a mut T; N];
+
+    /// Return mutable subslices of `self` for each of the
+    /// index ranges given by `ranges`. This is a many-way
+    /// [slice::split_at_mut]: the ranges need not be in order
+    /// or cover all of `self`.
+    ///
+    /// The ranges must not overlap, as Rust does not allow
+    /// multiple mutable references to the same object. Empty
+    /// ranges overlap nothing.
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::RangeBound] if any range
+    /// has its start after its end or its end after the end
+    /// of `self`. Otherwise, will return
+    /// [MutElemsError::RangesOverlap] if any pair of ranges
+    /// overlaps.
+    fn mut_ranges<'a, const N: usize>(
+        &'a mut self,
+        ranges: &[Range<usize>; N],
+    ) -> Result<[&'a mut [T]; N], MutElemsError>;
+
+    /// Return an iterator that lazily yields a mutable
+    /// reference to the element of `self` at each of the
+    /// index positions given by `indices`, checking each
+    /// index as it is reached.
+    ///
+    /// An index that is out of bounds, or that repeats an
+    /// index already seen, yields an error in place of a
+    /// reference, and iteration continues with the next
+    /// index. References already yielded for the earlier
+    /// occurrence of a repeated index remain valid.
+    ///
+    /// All yielded references may be held at once. The
+    /// iterator keeps the exclusive borrow of `self` and the
+    /// set of indices it has yielded, so it alone can vouch
+    /// that they are disjoint: safe code could not reborrow
+    /// `self` for each one while keeping the others.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3, 4];
+    /// // Stop at the first bad index, keeping what came before.
+    /// let mut total = 0;
+    /// let result = a.mut_elems_iter([3, 0, 3, 1]).try_for_each(|e| {
+    ///     let e = e?;
+    ///     total += *e;
+    ///     *e = 0;
+    ///     Ok::<_, MutElemsError>(())
+    /// });
+    /// assert!(result.is_err());
+    /// assert_eq!(5, total);
+    /// assert_eq!([0, 2, 3, 0], a);
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn mut_elems_iter<I>(&mut self, indices: I) -> MutElemsIter<'_, T, I::IntoIter>
+    where
+        I: IntoIterator<Item = usize>;
+
+    /// Return mutable references to elements of `self`
+    /// at each of the index positions given by `indices`,
+    /// which must be in strictly ascending order. This is
+    /// faster than [MutElemsExt::mut_elems], since only
+    /// adjacent indices need to be compared and only the last
+    /// index needs to be bounds-checked.
+    /// For indices that may instead be in descending order,
+    /// see [MutElemsExt::mut_elems_monotonic].
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::IndicesOverlap] if a pair
+    /// of adjacent indices is identical,
+    /// [MutElemsError::IndicesUnsorted] if an index is less
+    /// than the index before it, or an error if the last
+    /// index is out of bounds.
+    fn mut_elems_sorted<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+
+    /// Return mutable references to elements of `self`
+    /// at each of the index positions given by `indices`,
+    /// which may be of any [ElemIndex] type such as `u32`.
+    /// [MutElemsExt::mut_elems] itself takes `usize` indices
+    /// so that integer literal indices need no annotation.
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::IndexConversion] if any
+    /// index does not fit in a `usize`, or an error under the
+    /// same conditions as [MutElemsExt::mut_elems].
+    fn mut_elems_generic<'a, I: ElemIndex, const N: usize>(
+        &'a mut self,
+        indices: &[I; N],
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+
+    /// Return mutable references to elements of `self`
+    /// at each of the index positions given by `indices`,
+    /// where a negative index counts from the end: `-1` is
+    /// the last element.
+    ///
+    /// Indices are resolved to positions before checking, so
+    /// two indices resolving to the same position (such as
+    /// `0` and `-len`) overlap.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the resolved indices
+    /// are out of bounds, or if any pair of resolved indices
+    /// is identical. Errors report resolved indices; a
+    /// negative index reaching before the start of `self` is
+    /// reported out of bounds, with `index` holding the
+    /// negative index reinterpreted as a `usize`.
+    fn mut_elems_signed<'a, const N: usize>(
+        &'a mut self,
+        indices: &[isize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+
+    /// Return mutable references to elements of `self`
+    /// at each of the index positions given by `indices`,
+    /// or `None` wherever [MutElemsExt::mut_elems] would
+    /// return an error.
+    fn mut_elems_opt<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Option<[&'a mut T; N]>;
+
+    /// Call `f` with mutable references to elements of `self`
+    /// at each of the index positions given by `indices`, and
+    /// return its result. The references are scoped to the
+    /// call of `f`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error, without calling `f`, under the
+    /// same conditions as [MutElemsExt::mut_elems].
+    fn mut_elems_with<const N: usize, R>(
+        &mut self,
+        indices: &[usize; N],
+        f: impl FnOnce([&mut T; N]) -> R,
+    ) -> Result<R, MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// as many as possible of the index positions given by
+    /// `indices`, each paired with its position in `indices`,
+    /// together with an error for each index that could not
+    /// be satisfied.
+    ///
+    /// An index that is out of bounds yields
+    /// [MutElemsError::IndexBound]. An index that repeats an
+    /// earlier in-bounds index yields
+    /// [MutElemsError::IndicesOverlap]; the earlier occurrence
+    /// keeps its reference.
+    #[cfg(feature = "alloc")]
+    fn mut_elems_partial<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> (Vec<(usize, &'a mut T)>, Vec<MutElemsError>);
+
+    /// Return a map from each of the index positions given by
+    /// `indices` to a mutable reference to the element of
+    /// `self` at that position.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems_slice].
+    #[cfg(feature = "std")]
+    fn mut_elems_map<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<HashMap<usize, &'a mut T>, MutElemsError>;
+
+    /// Return a `Vec` of mutable references to elements of
+    /// `self` at each of the distinct index positions given
+    /// by `indices`. Unlike [MutElemsExt::mut_elems_slice],
+    /// repeated indices are not an error: only the first
+    /// occurrence of each index is kept, and the result
+    /// follows the order of first occurrences.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of
+    /// bounds.
+    #[cfg(feature = "alloc")]
+    fn mut_elems_dedup<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<Vec<&'a mut T>, MutElemsError>;
+
+    /// Return a tuple of mutable references to elements of
+    /// `self` at each of the index positions given by the
+    /// tuple `indices`, of arity 1 through 12.
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3, 4, 5, 6, 7, 8];
+    /// let (x, y, z) = a.mut_indexed((0, 3, 7)).unwrap();
+    /// *x += *y + *z;
+    /// assert_eq!(13, a[0]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems].
+    fn mut_indexed<'a, I: MutIndexTuple<'a, T>>(
+        &'a mut self,
+        indices: I,
+    ) -> Result<I::Output, MutElemsError>;
+
+    /// Split `self` into `N` disjoint mutable chunks covering
+    /// all of `self`, in order. Each chunk has
+    /// `self.len() / N` elements, except that the last chunk
+    /// also takes the remainder. In particular, when
+    /// `self.len() < N` every chunk but the last is empty.
+    fn chunks_disjoint_mut<const N: usize>(&mut self) -> [&mut [T]; N];
+
+    /// Return an array of mutable references to each of the
+    /// elements of `self`, in order, as with
+    /// [AsMutElemsExt::as_mut_elems]. This is for targets such
+    /// as `Vec` whose length is known only at runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = vec![1u8, 2, 3];
+    /// let [a, b, c] = v.as_mut_elems_array().unwrap();
+    /// core::mem::swap(a, c);
+    /// *b = 5;
+    /// assert_eq!(vec![3, 5, 1], v);
+    ///
+    /// let err = v.as_mut_elems_array::<2>().unwrap_err();
+    /// assert_eq!(MutElemsError::LengthMismatch { expected: 2, actual: 3 }, err);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::LengthMismatch] if
+    /// `self.len()` is not `N`.
+    fn as_mut_elems_array<const N: usize>(&mut self) -> Result<[&mut T; N], MutElemsError>;
+
+    /// Return an array of mutable references to each of the
+    /// elements of `self`, in order, if `self` has exactly
+    /// `N` elements. This is [MutElemsExt::as_mut_elems_array]
+    /// under the name of a fallible conversion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = vec![1u8, 2];
+    /// let s: &mut [u8] = &mut v;
+    /// let [a, b] = s.try_as_mut_elems().unwrap();
+    /// core::mem::swap(a, b);
+    /// assert_eq!([2, 1], *s);
+    /// assert!(s.try_as_mut_elems::<3>().is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::LengthMismatch] if
+    /// `self.len()` is not `N`.
+    fn try_as_mut_elems<const N: usize>(&mut self) -> Result<[&mut T; N], MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// `indices`, together with mutable references to
+    /// elements of `other` at `other_indices`. This is useful
+    /// for double-buffering, where elements of two separate
+    /// buffers are needed at once.
+    ///
+    /// Since `self` and `other` are distinct mutable borrows
+    /// they cannot alias, so indices are only checked for
+    /// overlap within each slice: the same index may appear
+    /// in both `indices` and `other_indices`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut front = [1u8, 2, 3];
+    /// let mut back = [0u8; 3];
+    /// let ([f0, f2], [b0]) = front.mut_elems_cross(&[0, 2], &mut back, &[0]).unwrap();
+    /// *b0 = *f0 + *f2;
+    /// *f0 = 0;
+    /// assert_eq!([0, 2, 3], front);
+    /// assert_eq!([4, 0, 0], back);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if either set of indices fails
+    /// the checks of [MutElemsExt::mut_elems] against its
+    /// slice. `indices` are checked first; positions in the
+    /// error refer to whichever set of indices failed.
+    fn mut_elems_cross<'a, 'b, const N: usize, const K: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+        other: &'b mut [T],
+        other_indices: &[usize; K],
+    ) -> Result<([&'a mut T; N], [&'b mut T; K]), MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `indices`, as
+    /// with [MutElemsExt::mut_elems]. On failure the error is
+    /// wrapped in [MutElemsError::WithIndices] along with a
+    /// copy of `indices`, for logging. Only the error path
+    /// allocates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3];
+    /// let err = a.mut_elems_verbose(&[0, 3]).unwrap_err();
+    /// assert_eq!(
+    ///     "index 1 is 3, but target length is 3 (indices [0, 3])",
+    ///     err.to_string(),
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::WithIndices] under the same
+    /// conditions as [MutElemsExt::mut_elems].
+    #[cfg(feature = "alloc")]
+    fn mut_elems_verbose<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `indices`, as
+    /// with [MutElemsExt::mut_elems], together with an
+    /// iterator over mutable references to all the other
+    /// elements of `self`, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3, 4, 5];
+    /// let ([pivot], rest) = a.mut_elems_rest(&[2]).unwrap();
+    /// for e in rest {
+    ///     *e += *pivot;
+    /// }
+    /// assert_eq!([4, 5, 3, 7, 8], a);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems].
+    fn mut_elems_rest<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<([&'a mut T; N], MutElemsRest<'a, T, N>), MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `indices`, as
+    /// with [MutElemsExt::mut_elems], wrapped in a
+    /// [MutElems] for indexing and iteration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3, 4];
+    /// let mut es = a.mut_elems_wrapped(&[3, 1]).unwrap();
+    /// es[0] += es[1];
+    /// for e in es {
+    ///     *e *= 2;
+    /// }
+    /// assert_eq!([1, 4, 3, 12], a);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems].
+    fn mut_elems_wrapped<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<MutElems<'a, T, N>, MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `indices`,
+    /// checking the indices for overlap but not for bounds.
+    /// This is a middle ground between
+    /// [MutElemsExt::mut_elems] and
+    /// [MutElemsExt::mut_elems_unchecked], for when the
+    /// indices are known to be in bounds but may repeat.
+    /// Bounds are still checked in debug builds.
+    ///
+    /// # Safety
+    ///
+    /// Every index must be in `0..self.len()`. Otherwise the
+    /// behavior is undefined.
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::IndicesOverlap] if any pair
+    /// of indices is identical.
+    ///
+    /// # Panics
+    ///
+    /// Will panic in debug builds if any index is out of
+    /// bounds.
+    unsafe fn mut_elems_checked_overlap_only<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+
+    /// Return a `Vec` of mutable references to elements of
+    /// `self` at each of the index positions given by
+    /// `indices`, as with [MutElemsExt::mut_elems_slice], but
+    /// in ascending order of index. Also returns the
+    /// permutation mapping each output position to the
+    /// position in `indices` it came from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3, 4];
+    /// let (es, perm) = a.mut_elems_ordered(&[3, 0, 2]).unwrap();
+    /// assert_eq!(vec![&1, &3, &4], es);
+    /// assert_eq!(vec![1, 2, 0], perm);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems_slice].
+    #[cfg(feature = "alloc")]
+    fn mut_elems_ordered<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<(Vec<&'a mut T>, Vec<usize>), MutElemsError>;
+
+    /// Return a `Vec` of mutable references to the elements of
+    /// `self` at each position where `mask` is `true`, in
+    /// ascending order of position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3, 4];
+    /// for e in a.mut_elems_masked(&[true, false, false, true]).unwrap() {
+    ///     *e *= 10;
+    /// }
+    /// assert_eq!([10, 2, 3, 40], a);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::LengthMismatch] if `mask`
+    /// is not the same length as `self`.
+    #[cfg(feature = "alloc")]
+    fn mut_elems_masked<'a>(&'a mut self, mask: &[bool]) -> Result<Vec<&'a mut T>, MutElemsError>;
+
+    /// Return a `Vec` of mutable subslices of `self`, each
+    /// `width` elements long, starting at each of the
+    /// positions given by `starts`. This is
+    /// [MutElemsExt::mut_ranges] for a runtime count of
+    /// equal-width windows.
+    ///
+    /// The windows must not overlap. When `width` is 0 the
+    /// windows are empty and overlap nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [0u8; 8];
+    /// for (w, x) in a.windows_disjoint_mut(&[6, 0, 3], 2).unwrap().into_iter().zip(1..) {
+    ///     w.fill(x);
+    /// }
+    /// assert_eq!([2, 2, 0, 3, 3, 0, 1, 1], a);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::RangeBound] if any window
+    /// extends past the end of `self`. Otherwise, will return
+    /// [MutElemsError::RangesOverlap] if any pair of windows
+    /// overlaps.
+    #[cfg(feature = "alloc")]
+    fn windows_disjoint_mut<'a>(
+        &'a mut self,
+        starts: &[usize],
+        width: usize,
+    ) -> Result<Vec<&'a mut [T]>, MutElemsError>;
+
+    /// Return a boxed slice of mutable references to elements
+    /// of `self` at each of the index positions given by
+    /// `indices`. This is [MutElemsExt::mut_elems_slice] for
+    /// results that are to be kept, without the spare
+    /// capacity of a `Vec`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems_slice].
+    #[cfg(feature = "alloc")]
+    fn mut_elems_boxed<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<Box<[&'a mut T]>, MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `indices`, as
+    /// with [MutElemsExt::mut_elems], each paired with the
+    /// index it came from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3, 4];
+    /// for (ix, e) in a.mut_elems_tagged(&[3, 1]).unwrap() {
+    ///     *e = ix as u8;
+    /// }
+    /// assert_eq!([1, 1, 3, 3], a);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems].
+    fn mut_elems_tagged<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[(usize, &'a mut T); N], MutElemsError>;
+
+    /// Return a `Vec` of mutable references to `count`
+    /// elements of `self`, at `start`, `start + step`,
+    /// `start + 2 * step` and so on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// // Interleaved stereo: left, right, left, right, ...
+    /// let mut samples = [1i16, 2, 3, 4, 5, 6];
+    /// for right in samples.mut_elems_stride(1, 2, 3).unwrap() {
+    ///     *right = -*right;
+    /// }
+    /// assert_eq!([1, -2, 3, -4, 5, -6], samples);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems_slice] would for the same
+    /// indices: in particular, a `step` of 0 with a `count`
+    /// greater than 1 repeats `start`.
+    #[cfg(feature = "alloc")]
+    fn mut_elems_stride(
+        &mut self,
+        start: usize,
+        step: usize,
+        count: usize,
+    ) -> Result<Vec<&mut T>, MutElemsError>;
+
+    /// Swap the elements of `self` at positions `i` and `j`
+    /// if `pred` holds for them, returning whether they were
+    /// swapped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// // A compare-and-swap: order positions 0 and 2.
+    /// let mut a = [3u8, 2, 1];
+    /// assert!(a.mut_swap_if(0, 2, |x, y| x > y).unwrap());
+    /// assert!(!a.mut_swap_if(0, 2, |x, y| x > y).unwrap());
+    /// assert_eq!([1, 2, 3], a);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if `i` or `j` is out of bounds, or
+    /// if `i` and `j` are equal.
+    fn mut_swap_if(
+        &mut self,
+        i: usize,
+        j: usize,
+        pred: impl FnOnce(&T, &T) -> bool,
+    ) -> Result<bool, MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `groups`, grouped
+    /// in the same way. No index may appear twice, whether in
+    /// the same group or in different groups.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3, 4, 5, 6];
+    /// let [[a0, a1], [b0, b1]] = a.mut_elems_grouped(&[[0, 1], [4, 5]]).unwrap();
+    /// core::mem::swap(a0, b0);
+    /// core::mem::swap(a1, b1);
+    /// assert_eq!([5, 6, 3, 4, 1, 2], a);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems] would for the groups
+    /// concatenated, with positions in the error counting
+    /// through the groups in order: the position of index `k`
+    /// of group `g` is `g * N + k`.
+    fn mut_elems_grouped<'a, const G: usize, const N: usize>(
+        &'a mut self,
+        groups: &[[usize; N]; G],
+    ) -> Result<[[&'a mut T; N]; G], MutElemsError>;
+
+    /// Return an `ArrayVec` of mutable references to elements
+    /// of `self` at each of the index positions given by
+    /// `indices`. This is [MutElemsExt::mut_elems_slice] for
+    /// a runtime count of indices known to be at most `MAX`,
+    /// without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3, 4];
+    /// let es = a.mut_elems_arrayvec::<4>(&[3, 1]).unwrap();
+    /// for e in es {
+    ///     *e = 0;
+    /// }
+    /// assert_eq!([1, 0, 3, 0], a);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::CapacityExceeded] if there
+    /// are more than `MAX` indices, or otherwise an error under
+    /// the same conditions as [MutElemsExt::mut_elems_slice].
+    #[cfg(feature = "arrayvec")]
+    fn mut_elems_arrayvec<'a, const MAX: usize>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<arrayvec::ArrayVec<&'a mut T, MAX>, MutElemsError>;
+
+    /// Call `f` on the element of `self` at each of the
+    /// index positions given by `indices`, in order, along
+    /// with the position of the index in `indices`. Unlike
+    /// [MutElemsExt::mut_elems], repeated indices are
+    /// allowed: each occurrence is handled in turn, so this
+    /// suits accumulations such as histograms.
+    ///
+    /// All indices are checked before `f` is first called,
+    /// so on error `self` is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut bins = [0u32; 4];
+    /// bins.scatter_accumulate(&[1, 3, 1, 1], |bin, _| *bin += 1)
+    ///     .unwrap();
+    /// assert_eq!([0, 3, 0, 1], bins);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of
+    /// bounds.
+    fn scatter_accumulate<F: FnMut(&mut T, usize)>(
+        &mut self,
+        indices: &[usize],
+        f: F,
+    ) -> Result<(), MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `indices`, as
+    /// with [MutElemsExt::mut_elems_slice], together with a
+    /// read-only view of the elements not chosen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// // Replace chosen elements with the sum of their
+    /// // unchosen neighbours.
+    /// let mut v = [1u8, 0, 2, 0, 3];
+    /// let (es, rest) = v.mut_elems_split(&[1, 3]).unwrap();
+    /// for (e, ix) in es.into_iter().zip([1, 3]) {
+    ///     *e = rest[ix - 1] + rest[ix + 1];
+    /// }
+    /// assert_eq!([1, 3, 2, 5, 3], v);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of
+    /// bounds, or if any pair of indices is identical.
+    #[cfg(feature = "alloc")]
+    fn mut_elems_split<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<(Vec<&'a mut T>, SplitRest<'a, T>), MutElemsError>;
+
+    /// Return mutable references to the first `N` elements
+    /// of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = [1u8, 2, 3, 4];
+    /// let [a, b] = v.mut_first().unwrap();
+    /// core::mem::swap(a, b);
+    /// assert_eq!([2, 1, 3, 4], v);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::LengthMismatch] if there
+    /// are fewer than `N` elements.
+    fn mut_first<const N: usize>(&mut self) -> Result<[&mut T; N], MutElemsError>;
+
+    /// Return mutable references to the last `N` elements of
+    /// `self`, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = [1u8, 2, 3, 4];
+    /// let [a, b] = v.mut_last().unwrap();
+    /// core::mem::swap(a, b);
+    /// assert_eq!([1, 2, 4, 3], v);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::LengthMismatch] if there
+    /// are fewer than `N` elements.
+    fn mut_last<const N: usize>(&mut self) -> Result<[&mut T; N], MutElemsError>;
+
+    /// Move each of `values` into the element of `self` at
+    /// the corresponding index position given by `indices`.
+    ///
+    /// All indices are checked before anything is written, so
+    /// on error `self` is unchanged and `values` are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = [0u8; 4];
+    /// v.write_elems(&[3, 0], [7, 9]).unwrap();
+    /// assert_eq!([9, 0, 0, 7], v);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of
+    /// bounds, or if any pair of indices is identical.
+    fn write_elems<const N: usize>(
+        &mut self,
+        indices: &[usize; N],
+        values: [T; N],
+    ) -> Result<(), MutElemsError>;
+
+    /// Rearrange the elements of `self` at the index
+    /// positions given by `indices`, so that the element at
+    /// `indices[k]` becomes the one previously at
+    /// `indices[perm[k]]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = ['a', 'b', 'c', 'd'];
+    /// v.permute_elems(&[0, 1, 3], &[2, 0, 1]).unwrap();
+    /// assert_eq!(['d', 'a', 'c', 'b'], v);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::InvalidPermutation] if
+    /// `perm` is not a permutation of `0..N`, or an error if
+    /// any of the indices are out of bounds or any pair of
+    /// indices is identical. On error `self` is unchanged.
+    fn permute_elems<const N: usize>(
+        &mut self,
+        indices: &[usize; N],
+        perm: &[usize; N],
+    ) -> Result<(), MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `indices`, as
+    /// with [MutElemsExt::mut_elems]. On failure the error
+    /// comes with a copy of `indices` in a
+    /// [MutElemsDiagnostic]. Only the error path allocates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3];
+    /// let err = a.mut_elems_diagnostic(&[2, 0, 2]).unwrap_err();
+    /// assert_eq!(MutElemsErrorKind::Overlap, err.error.kind());
+    /// assert_eq!(vec![0, 2, 2], err.sorted_indices());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return a [MutElemsDiagnostic] under the same
+    /// conditions as [MutElemsExt::mut_elems].
+    #[cfg(feature = "alloc")]
+    fn mut_elems_diagnostic<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsDiagnostic>;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions in `indices`, which are
+    /// already known to be distinct, so only bounds are
+    /// checked.
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::IndexBound] if any of the
+    /// indices are out of bounds.
+    #[cfg(feature = "alloc")]
+    fn mut_elems_distinct<'a>(
+        &'a mut self,
+        indices: &DistinctIndices,
+    ) -> Result<Vec<&'a mut T>, MutElemsError>;
+
+    /// Return mutable references to elements of `self`
+    /// at each of the index positions given by `indices`,
+    /// which must be in strictly ascending or strictly
+    /// descending order, as with [MutElemsExt::mut_elems_sorted].
+    /// The direction is taken from the first two indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3, 4];
+    /// let [x, y, z] = a.mut_elems_monotonic(&[3, 2, 0]).unwrap();
+    /// *x += *y + *z;
+    /// assert_eq!([1, 2, 3, 8], a);
+    /// assert!(a.mut_elems_monotonic(&[3, 0, 2]).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::IndicesOverlap] if a pair
+    /// of adjacent indices is identical,
+    /// [MutElemsError::IndicesUnsorted] if an index is out of
+    /// order with respect to the index before it, or an error
+    /// if the largest index is out of bounds.
+    fn mut_elems_monotonic<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+
+    /// Sort `indices` in place, then return mutable references
+    /// to elements of `self` at each of them, in the sorted
+    /// order, as with [MutElemsExt::mut_elems_sorted]. This
+    /// checks any indices for distinctness without extra
+    /// space.
+    ///
+    /// `indices` is left sorted on return, whether or not
+    /// there is an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3, 4];
+    /// let mut indices = [3, 0, 2];
+    /// let [x, y, z] = a.mut_elems_sort_in_place(&mut indices).unwrap();
+    /// assert_eq!((1, 3, 4), (*x, *y, *z));
+    /// assert_eq!([0, 2, 3], indices);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of
+    /// bounds, or if any pair of indices is identical. The
+    /// positions in the error are positions in the sorted
+    /// `indices`.
+    fn mut_elems_sort_in_place<'a, const N: usize>(
+        &'a mut self,
+        indices: &mut [usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `indices`, as
+    /// with [MutElemsExt::mut_elems], but reusing `scratch`
+    /// for the overlap check. Many sparse indices on a long
+    /// target are checked by sorting, which otherwise needs a
+    /// buffer of `N` pairs on the stack on each call; keeping
+    /// `scratch` across calls in a loop holds that buffer on
+    /// the heap, allocated once. `scratch` is cleared on each
+    /// call, and its contents afterward are unspecified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = vec![0u32; 1 << 16];
+    /// let mut scratch = Vec::new();
+    /// for round in 0..4 {
+    ///     let indices: [usize; 100] = core::array::from_fn(|i| i * 600 + round);
+    ///     for e in v.mut_elems_with_scratch(&indices, &mut scratch).unwrap() {
+    ///         *e += 1;
+    ///     }
+    /// }
+    /// assert_eq!(400, v.iter().sum::<u32>());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems].
+    #[cfg(feature = "alloc")]
+    fn mut_elems_with_scratch<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+        scratch: &mut Vec<(usize, usize)>,
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `indices`. In
+    /// debug builds the indices are fully checked, with a
+    /// panic naming the error and all the indices if they
+    /// are bad; in release builds this is
+    /// [MutElemsExt::mut_elems_unchecked]. This suits call
+    /// sites that are known correct but should be caught in
+    /// testing if they are not.
+    ///
+    /// Since release builds skip the checks, this must be an
+    /// `unsafe fn`: a safe signature would let safe code
+    /// reach undefined behavior with bad indices.
+    ///
+    /// # Safety
+    ///
+    /// Every index must be in `0..self.len()`, and the
+    /// indices must be pairwise distinct. Otherwise the
+    /// behavior is undefined in release builds.
+    ///
+    /// # Panics
+    ///
+    /// Will panic in debug builds if any index is out of
+    /// bounds, or if any pair of indices is identical.
+    unsafe fn mut_elems_dbg<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> [&'a mut T; N];
+
+    /// Return an iterator over `(index, reference)` pairs for
+    /// every element of `self` whose index is not in
+    /// `excluded`, in order. Indices in `excluded` may repeat
+    /// and be in any order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = [1u8, 2, 3, 4, 5];
+    /// for (_, e) in v.iter_mut_except(&[3, 0, 3]).unwrap() {
+    ///     *e = 0;
+    /// }
+    /// assert_eq!([1, 0, 0, 4, 0], v);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::IndexBound] if any of the
+    /// excluded indices are out of bounds.
+    #[cfg(feature = "alloc")]
+    fn iter_mut_except(
+        &mut self,
+        excluded: &[usize],
+    ) -> Result<IterMutExcept<'_, T>, MutElemsError>;
+
+    /// Return mutable references to elements of `self` at
+    /// the index positions chosen by `select`, which is first
+    /// given a shared view of `self`. The shared borrow ends
+    /// before the mutable references are made.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// // Zero the two largest elements.
+    /// let mut v = [3u8, 9, 1, 7, 4];
+    /// let es = v
+    ///     .mut_elems_by(|s| {
+    ///         let mut order: [usize; 5] = core::array::from_fn(|i| i);
+    ///         order.sort_by_key(|&i| core::cmp::Reverse(s[i]));
+    ///         [order[0], order[1]]
+    ///     })
+    ///     .unwrap();
+    /// es.into_iter().for_each(|e| *e = 0);
+    /// assert_eq!([3, 0, 1, 0, 4], v);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems] for the chosen indices.
+    fn mut_elems_by<const N: usize, F: FnOnce(&[T]) -> [usize; N]>(
+        &mut self,
+        select: F,
+    ) -> Result<[&mut T; N], MutElemsError>;
+
+    /// Call `f` on mutable references to elements of `self`
+    /// at each of the index positions given by `indices`, as
+    /// with [MutElemsExt::mut_elems]. If `f` fails, the
+    /// elements are restored to clones taken beforehand, so
+    /// that either all of the changes made by `f` are kept or
+    /// none are. If `f` panics, its changes are not undone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// // Move 5 from one account to another, unless that
+    /// // would overdraw.
+    /// let mut accounts = [10u32, 3, 0];
+    /// let transfer = |[from, to]: [&mut u32; 2]| {
+    ///     *to += 5;
+    ///     *from = from.checked_sub(5).ok_or("overdrawn")?;
+    ///     Ok(())
+    /// };
+    /// assert_eq!(Ok(Ok(())), accounts.mut_elems_transact(&[0, 2], transfer));
+    /// assert_eq!([5, 3, 5], accounts);
+    /// assert_eq!(Ok(Err("overdrawn")), accounts.mut_elems_transact(&[1, 2], transfer));
+    /// assert_eq!([5, 3, 5], accounts);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error, without calling `f`, under the
+    /// same conditions as [MutElemsExt::mut_elems].
+    /// Otherwise the result of `f` is returned.
+    fn mut_elems_transact<const N: usize, E, F>(
+        &mut self,
+        indices: &[usize; N],
+        f: F,
+    ) -> Result<Result<(), E>, MutElemsError>
+    where
+        T: Clone,
+        F: FnOnce([&mut T; N]) -> Result<(), E>;
+
+    /// Return mutable references to the `K` smallest elements
+    /// of `self`, smallest first. Equal elements are taken in
+    /// index order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut scores = [7u8, 3, 9, 3, 5];
+    /// let [lo, next] = scores.mut_k_smallest().unwrap();
+    /// *lo = 0;
+    /// *next = 1;
+    /// assert_eq!([7, 0, 9, 1, 5], scores);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::LengthMismatch] if `K` is
+    /// greater than `self.len()`.
+    fn mut_k_smallest<const K: usize>(&mut self) -> Result<[&mut T; K], MutElemsError>
+    where
+        T: Ord;
+
+    /// Return mutable references to the `K` largest elements
+    /// of `self`, largest first. Equal elements are taken in
+    /// index order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut scores = [7u8, 3, 9, 3, 7];
+    /// let [hi, next] = scores.mut_k_largest().unwrap();
+    /// *hi = 10;
+    /// *next = 8;
+    /// assert_eq!([8, 3, 10, 3, 7], scores);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::LengthMismatch] if `K` is
+    /// greater than `self.len()`.
+    fn mut_k_largest<const K: usize>(&mut self) -> Result<[&mut T; K], MutElemsError>
+    where
+        T: Ord;
+
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `indices`, as
+    /// with [MutElemsExt::mut_elems], together with a
+    /// read-only view of the elements not chosen. This is
+    /// [MutElemsExt::mut_elems_split] for a count of indices
+    /// known at compile time, and needs no allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// // Smooth two elements using their unchosen neighbours.
+    /// let mut v = [2u8, 9, 4, 9, 6];
+    /// let (es, rest) = v.mut_elems_and_rest_ref(&[1, 3]).unwrap();
+    /// for (e, ix) in es.into_iter().zip([1, 3]) {
+    ///     *e = (rest[ix - 1] + rest[ix + 1]) / 2;
+    /// }
+    /// assert_eq!([2, 3, 4, 5, 6], v);
+    /// ```
+    ///
+    /// No chosen element can be read through the view.
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = [1u8, 2, 3];
+    /// let ([e], rest) = v.mut_elems_and_rest_ref(&[1]).unwrap();
+    /// assert_eq!(None, rest.get(1));
+    /// *e = rest[0] + rest[2];
+    /// assert_eq!([1, 4, 3], v);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of
+    /// bounds, or if any pair of indices is identical.
+    fn mut_elems_and_rest_ref<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<([&'a mut T; N], RestRef<'a, T, N>), MutElemsError>;
+}
+
+/// Mutable references to every element of an array.
+///
+/// As with [MutElemsExt], the references are independent even
+/// when `T` is zero-sized.
+///
+/// The methods can be called on a `&mut [T; N]` as well, such
+/// as an array passed by reference, by auto-dereference.
+pub trait AsMutElemsExt<const N: usize, T> {
+    /// Return an array of mutable references to each
+    /// of the elements of the input array.
+    ///
+    /// The length `N` is part of the result type, so generic
+    /// code can carry it along and the compiler checks it:
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// fn take_all<const N: usize>(es: [&mut u32; N]) -> [u32; N] {
+    ///     es.map(|e| core::mem::take(e))
+    /// }
+    ///
+    /// fn drain<const N: usize>(a: &mut [u32; N]) -> [u32; N] {
+    ///     take_all(a.as_mut_elems())
+    /// }
+    ///
+    /// let mut a = [1, 2, 3, 4];
+    /// assert_eq!([1, 2, 3, 4], drain(&mut a));
+    /// assert_eq!([0; 4], a);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u32, 2, 3, 4];
+    /// let es: [&mut u32; 3] = a.as_mut_elems();
+    /// ```
+    fn as_mut_elems(&mut self) -> [&mut T; N];
+}
+
+/// Mutable references to every element of a slice, array or
+/// `Vec`.
+///
+/// As with [MutElemsExt], the references are independent even
+/// when `T` is zero-sized.
+#[cfg(feature = "alloc")]
+pub trait AsMutElemsVecExt<T> {
+    /// Return a `Vec` of mutable references to each
+    /// of the elements of the input `Vec`.
+    fn as_mut_elems_vec(&mut self) -> Vec<&mut T>;
+
+    /// Return a `SmallVec` of mutable references to each of
+    /// the elements of the input, stored inline without
+    /// allocating when there are at most `INLINE` elements.
+    #[cfg(feature = "smallvec")]
+    fn as_mut_elems_smallvec<const INLINE: usize>(
+        &mut self,
+    ) -> smallvec::SmallVec<[&mut T; INLINE]>;
+
+    /// Return a `Vec` of mutable references to the elements
+    /// of the input at each of the index positions given by
+    /// `indices`, as with [MutElemsExt::mut_elems_slice].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = vec![1u8, 2, 3, 4];
+    /// let mut es = v.as_mut_elems_vec_indexed(&[3, 1]).unwrap();
+    /// *es[0] += 10;
+    /// *es[1] += 20;
+    /// assert_eq!(vec![1, 22, 3, 14], v);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of
+    /// bounds, or if any pair of indices is identical.
+    fn as_mut_elems_vec_indexed(&mut self, indices: &[usize])
+        -> Result<Vec<&mut T>, MutElemsError>;
+}
+
+impl<T> MutElemsExt<T> for [T] {
+    // Inlined, like the index checks, so that callers can see
+    // it cannot panic: see `tests/no-panic.rs`.
+    #[inline]
+    fn mut_elems<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        let length = self.len();
+        check_indices_in(indices, length)?;
+
+        // Safety: Indices have been checked for inequality, so
+        // they must indicate unique locations.  Bounds checking
+        // has already been done.
+        Ok(unsafe { self.mut_elems_unchecked(indices) })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_slice<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<Vec<&'a mut T>, MutElemsError> {
+        let length = self.len();
+        check_indices_slice_in(indices, length)?;
+
+        // Safety: Indices have been checked for inequality and
+        // bounds, as with `mut_elems()`. All references are
+        // derived from a single base pointer, as with
+        // `mut_elems_unchecked()`.
+        let base = self.as_mut_ptr();
+        Ok(indices
+            .iter()
+            .map(|&ix| unsafe { &mut *base.add(ix) })
+            .collect())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_report_all<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        match check_indices_in(indices, self.len()) {
+            Err(IndicesOverlap { .. }) => {
+                return Err(IndicesOverlapAll {
+                    groups: overlap_groups(indices),
+                })
+            }
+            result => result?,
+        }
+
+        // Safety: Indices have been checked for inequality and
+        // bounds, as with `mut_elems()`.
+        Ok(unsafe { self.mut_elems_unchecked(indices) })
+    }
+
+    fn get_disjoint_mut<const N: usize>(
+        &mut self,
+        indices: [usize; N],
+    ) -> Result<[&mut T; N], MutElemsError> {
+        self.mut_elems(&indices)
+    }
+
+    unsafe fn mut_elems_unchecked<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> [&'a mut T; N] {
+        // Safety: The caller guarantees that the indices are
+        // in-bounds and indicate unique locations, so we can
+        // bypass checking them.  `from_fn()` guarantees that
+        // `i` is in-bounds, so we can bypass checking that.
+        // All references are derived from a single base
+        // pointer: reborrowing `self` for each element would
+        // invalidate the references already made.
+        let base = self.as_mut_ptr();
+        core::array::from_fn(|i| unsafe { &mut *base.add(*indices.get_unchecked(i)) })
+    }
+
+    fn mut_ranges<'a, const N: usize>(
+        &'a mut self,
+        ranges: &[Range<usize>; N],
+    ) -> Result<[&'a mut [T]; N], MutElemsError> {
+        let length = self.len();
+        for (position, r) in ranges.iter().enumerate() {
+            if r.start > r.end || r.end > length {
+                return Err(RangeBound {
+                    position,
+                    start: r.start,
+                    end: r.end,
+                    length,
+                });
+            }
+        }
+
+        // Sort the ranges by start, then check that each
+        // nonempty range ends before the next one starts.
+        let mut starts: [(usize, usize, usize); N] =
+            core::array::from_fn(|i| (ranges[i].start, ranges[i].end, i));
+        starts.sort_unstable();
+        let mut prev: Option<(usize, usize)> = None;
+        for &(start, end, position) in starts.iter().filter(|(s, e, _)| s < e) {
+            if let Some((prev_end, prev_position)) = prev {
+                if prev_end > start {
+                    return Err(RangesOverlap {
+                        first: prev_position.min(position),
+                        second: prev_position.max(position),
+                    });
+                }
+            }
+            prev = Some((end, position));
+        }
+
+        // Safety: The ranges have been checked to be within
+        // bounds and pairwise disjoint, so the subslices are
+        // valid and do not alias.
+        let base = self.as_mut_ptr();
+        Ok(core::array::from_fn(|i| unsafe {
+            let r = ranges.get_unchecked(i);
+            core::slice::from_raw_parts_mut(base.add(r.start), r.end - r.start)
+        }))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_iter<I>(&mut self, indices: I) -> MutElemsIter<'_, T, I::IntoIter>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        MutElemsIter {
+            base: self.as_mut_ptr(),
+            length: self.len(),
+            indices: indices.into_iter(),
+            position: 0,
+            seen: BTreeMap::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    fn mut_elems_sorted<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        for (i, w) in indices.windows(2).enumerate() {
+            if w[0] == w[1] {
+                return Err(IndicesOverlap {
+                    first: i,
+                    second: i + 1,
+                    index: w[1],
+                });
+            }
+            if w[0] > w[1] {
+                return Err(IndicesUnsorted { position: i + 1 });
+            }
+        }
+        // Only the last, largest index can be out of bounds.
+        if let Some(&index) = indices.last() {
+            let length = self.len();
+            if index >= length {
+                return Err(IndexBound {
+                    position: N - 1,
+                    index,
+                    length,
+                });
+            }
+        }
+
+        // Safety: Indices are strictly ascending, so they must
+        // indicate unique locations, and the largest is in
+        // bounds.
+        Ok(unsafe { self.mut_elems_unchecked(indices) })
+    }
+
+    fn mut_elems_generic<'a, I: ElemIndex, const N: usize>(
+        &'a mut self,
+        indices: &[I; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        let mut converted = [0; N];
+        for (position, (c, ix)) in converted.iter_mut().zip(indices).enumerate() {
+            *c = ix.to_usize().ok_or(IndexConversion { position })?;
+        }
+        self.mut_elems(&converted)
+    }
+
+    fn mut_elems_signed<'a, const N: usize>(
+        &'a mut self,
+        indices: &[isize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        // A negative index too large to resolve is left as
+        // its `usize` reinterpretation, which is at least
+        // `2**(usize::BITS - 1)` and so is out of bounds and
+        // cannot equal any other resolved index but itself.
+        let length = self.len();
+        let resolved: [usize; N] = core::array::from_fn(|i| {
+            let ix = indices[i];
+            if ix < 0 {
+                length.checked_sub(ix.unsigned_abs()).unwrap_or(ix as usize)
+            } else {
+                ix as usize
+            }
+        });
+        self.mut_elems(&resolved)
+    }
+
+    fn mut_elems_opt<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Option<[&'a mut T; N]> {
+        self.mut_elems(indices).ok()
+    }
+
+    fn mut_elems_with<const N: usize, R>(
+        &mut self,
+        indices: &[usize; N],
+        f: impl FnOnce([&mut T; N]) -> R,
+    ) -> Result<R, MutElemsError> {
+        self.mut_elems(indices).map(f)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_partial<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> (Vec<(usize, &'a mut T)>, Vec<MutElemsError>) {
+        let mut found = Vec::with_capacity(indices.len());
+        let mut errors = Vec::new();
+        for (position, result) in self.mut_elems_iter(indices.iter().copied()).enumerate() {
+            match result {
+                Ok(e) => found.push((position, e)),
+                Err(e) => errors.push(e),
+            }
+        }
+        (found, errors)
+    }
+
+    #[cfg(feature = "std")]
+    fn mut_elems_map<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<HashMap<usize, &'a mut T>, MutElemsError> {
+        let es = self.mut_elems_slice(indices)?;
+        Ok(indices.iter().copied().zip(es).collect())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_dedup<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<Vec<&'a mut T>, MutElemsError> {
+        check_bounds(indices, self.len())?;
+
+        // Mark the first occurrence of each index: sorting
+        // puts it first among its repeats.
+        let mut pairs: Vec<(usize, usize)> = indices.iter().copied().zip(0..).collect();
+        pairs.sort_unstable();
+        let mut keep = alloc::vec![false; indices.len()];
+        for group in pairs.chunk_by(|(ix0, _), (ix1, _)| ix0 == ix1) {
+            keep[group[0].1] = true;
+        }
+
+        // Safety: Indices have been checked for bounds, and
+        // only one occurrence of each is kept.
+        let base = self.as_mut_ptr();
+        Ok(indices
+            .iter()
+            .zip(keep)
+            .filter(|&(_, k)| k)
+            .map(|(&ix, _)| unsafe { &mut *base.add(ix) })
+            .collect())
+    }
+
+    fn mut_indexed<'a, I: MutIndexTuple<'a, T>>(
+        &'a mut self,
+        indices: I,
+    ) -> Result<I::Output, MutElemsError> {
+        indices.mut_elems_of(self)
+    }
+
+    fn chunks_disjoint_mut<const N: usize>(&mut self) -> [&mut [T]; N] {
+        let chunk = self.len().checked_div(N).unwrap_or(0);
+        let mut rest = self;
+        core::array::from_fn(|i| {
+            let n = if i + 1 == N { rest.len() } else { chunk };
+            let (c, r) = core::mem::take(&mut rest).split_at_mut(n);
+            rest = r;
+            c
+        })
+    }
+
+    fn as_mut_elems_array<const N: usize>(&mut self) -> Result<[&mut T; N], MutElemsError> {
+        let actual = self.len();
+        if actual != N {
+            return Err(LengthMismatch {
+                expected: N,
+                actual,
+            });
+        }
+
+        // Safety: The `N` indices `0..N` are unique and, having
+        // checked the length, in-bounds. All references are
+        // derived from a single base pointer, as with
+        // `mut_elems_unchecked()`.
+        let base = self.as_mut_ptr();
+        Ok(core::array::from_fn(|i| unsafe { &mut *base.add(i) }))
+    }
+
+    fn try_as_mut_elems<const N: usize>(&mut self) -> Result<[&mut T; N], MutElemsError> {
+        self.as_mut_elems_array()
+    }
+
+    fn mut_elems_cross<'a, 'b, const N: usize, const K: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+        other: &'b mut [T],
+        other_indices: &[usize; K],
+    ) -> Result<([&'a mut T; N], [&'b mut T; K]), MutElemsError> {
+        let es = self.mut_elems(indices)?;
+        let other_es = other.mut_elems(other_indices)?;
+        Ok((es, other_es))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_verbose<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        self.mut_elems(indices).map_err(|error| WithIndices {
+            error: Box::new(error),
+            indices: Box::new(*indices),
+        })
+    }
+
+    fn mut_elems_rest<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<([&'a mut T; N], MutElemsRest<'a, T, N>), MutElemsError> {
+        let length = self.len();
+        check_indices_in(indices, length)?;
+
+        let mut skip = *indices;
+        skip.sort_unstable();
+
+        // Safety: Indices have been checked for inequality and
+        // bounds, as with `mut_elems()`. The rest iterator
+        // skips exactly the chosen indices, so it never
+        // yields a reference aliasing one of them. All
+        // references are derived from a single base pointer,
+        // as with `mut_elems_unchecked()`.
+        let base = self.as_mut_ptr();
+        let es = indices.map(|ix| unsafe { &mut *base.add(ix) });
+        let rest = MutElemsRest {
+            base,
+            length,
+            position: 0,
+            skip,
+            next_skip: 0,
+            phantom: PhantomData,
+        };
+        Ok((es, rest))
+    }
+
+    fn mut_elems_wrapped<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<MutElems<'a, T, N>, MutElemsError> {
+        self.mut_elems(indices).map(MutElems)
+    }
+
+    unsafe fn mut_elems_checked_overlap_only<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        debug_assert!(
+            check_bounds(indices, self.len()).is_ok(),
+            "index out of bounds in mut_elems_checked_overlap_only()",
+        );
+        check_distinct(indices)?;
+
+        // Safety: Indices have been checked for inequality,
+        // and the caller guarantees bounds.
+        Ok(unsafe { self.mut_elems_unchecked(indices) })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_ordered<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<(Vec<&'a mut T>, Vec<usize>), MutElemsError> {
+        check_indices_slice_in(indices, self.len())?;
+
+        let mut perm: Vec<usize> = (0..indices.len()).collect();
+        perm.sort_unstable_by_key(|&p| indices[p]);
+
+        // Safety: Indices have been checked for inequality and
+        // bounds, as with `mut_elems()`. All references are
+        // derived from a single base pointer, as with
+        // `mut_elems_unchecked()`.
+        let base = self.as_mut_ptr();
+        let es = perm
+            .iter()
+            .map(|&p| unsafe { &mut *base.add(indices[p]) })
+            .collect();
+        Ok((es, perm))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_masked<'a>(&'a mut self, mask: &[bool]) -> Result<Vec<&'a mut T>, MutElemsError> {
+        if mask.len() != self.len() {
+            return Err(LengthMismatch {
+                expected: self.len(),
+                actual: mask.len(),
+            });
+        }
+
+        // Iteration guarantees that elements are in-bounds and
+        // unique.
+        Ok(self
+            .iter_mut()
+            .zip(mask)
+            .filter_map(|(e, &m)| m.then_some(e))
+            .collect())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn windows_disjoint_mut<'a>(
+        &'a mut self,
+        starts: &[usize],
+        width: usize,
+    ) -> Result<Vec<&'a mut [T]>, MutElemsError> {
+        let length = self.len();
+        for (position, &start) in starts.iter().enumerate() {
+            match start.checked_add(width) {
+                Some(end) if end <= length => (),
+                end => {
+                    return Err(RangeBound {
+                        position,
+                        start,
+                        end: end.unwrap_or(usize::MAX),
+                        length,
+                    })
+                }
+            }
+        }
+
+        // With equal widths, sorted windows overlap exactly
+        // when adjacent starts are closer than the width.
+        if width > 0 {
+            let mut sorted: Vec<(usize, usize)> = starts.iter().copied().zip(0..).collect();
+            sorted.sort_unstable();
+            for w in sorted.windows(2) {
+                let ((start0, p0), (start1, p1)) = (w[0], w[1]);
+                if start1 - start0 < width {
+                    return Err(RangesOverlap {
+                        first: p0.min(p1),
+                        second: p0.max(p1),
+                    });
+                }
+            }
+        }
+
+        // Safety: The windows have been checked to be within
+        // bounds and pairwise disjoint, so the subslices are
+        // valid and do not alias.
+        let base = self.as_mut_ptr();
+        Ok(starts
+            .iter()
+            .map(|&start| unsafe { core::slice::from_raw_parts_mut(base.add(start), width) })
+            .collect())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_boxed<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<Box<[&'a mut T]>, MutElemsError> {
+        check_indices_slice_in(indices, self.len())?;
+
+        // Safety: Indices have been checked for inequality and
+        // bounds, as with `mut_elems_slice()`.
+        let base = self.as_mut_ptr();
+        Ok(indices
+            .iter()
+            .map(|&ix| unsafe { &mut *base.add(ix) })
+            .collect())
+    }
+
+    fn mut_elems_tagged<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[(usize, &'a mut T); N], MutElemsError> {
+        let es = self.mut_elems(indices)?;
+        let mut i = 0;
+        Ok(es.map(|e| {
+            i += 1;
+            (indices[i - 1], e)
+        }))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_stride(
+        &mut self,
+        start: usize,
+        step: usize,
+        count: usize,
+    ) -> Result<Vec<&mut T>, MutElemsError> {
+        let length = self.len();
+        if count > 0 && start >= length {
+            return Err(IndexBound {
+                position: 0,
+                index: start,
+                length,
+            });
+        }
+        if count > 1 && step == 0 {
+            return Err(IndicesOverlap {
+                first: 0,
+                second: 1,
+                index: start,
+            });
+        }
+        // The first position past the end, if any: indices
+        // with a nonzero step are distinct, so this is the
+        // only possible error.
+        if count > 1 {
+            let position = (length - start).div_ceil(step);
+            if position < count {
+                return Err(IndexBound {
+                    position,
+                    index: start.saturating_add(position.saturating_mul(step)),
+                    length,
+                });
+            }
+        }
+
+        // Safety: The indices are distinct and have been
+        // checked for bounds. All references are derived from
+        // a single base pointer, as with `mut_elems_slice()`.
+        let base = self.as_mut_ptr();
+        Ok((0..count)
+            .map(|i| unsafe { &mut *base.add(start + i * step) })
+            .collect())
+    }
+
+    fn mut_swap_if(
+        &mut self,
+        i: usize,
+        j: usize,
+        pred: impl FnOnce(&T, &T) -> bool,
+    ) -> Result<bool, MutElemsError> {
+        let [a, b] = self.mut_elems(&[i, j])?;
+        let swap = pred(a, b);
+        if swap {
+            core::mem::swap(a, b);
+        }
+        Ok(swap)
+    }
+
+    fn mut_elems_grouped<'a, const G: usize, const N: usize>(
+        &'a mut self,
+        groups: &[[usize; N]; G],
+    ) -> Result<[[&'a mut T; N]; G], MutElemsError> {
+        check_indices_slice_in(groups.as_flattened(), self.len())?;
+
+        // Safety: Indices have been checked for inequality and
+        // bounds across all groups, as with `mut_elems()`. All
+        // references are derived from a single base pointer,
+        // as with `mut_elems_unchecked()`.
+        let base = self.as_mut_ptr();
+        Ok(groups.map(|group| group.map(|ix| unsafe { &mut *base.add(ix) })))
+    }
+
+    #[cfg(feature = "arrayvec")]
+    fn mut_elems_arrayvec<'a, const MAX: usize>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<arrayvec::ArrayVec<&'a mut T, MAX>, MutElemsError> {
+        if indices.len() > MAX {
+            return Err(CapacityExceeded {
+                count: indices.len(),
+                capacity: MAX,
+            });
+        }
+        check_indices_slice_in(indices, self.len())?;
+
+        // Safety: Indices have been checked for inequality and
+        // bounds, as with `mut_elems_slice()`. There are at
+        // most `MAX`, so they fit.
+        let base = self.as_mut_ptr();
+        let mut es = arrayvec::ArrayVec::new();
+        for &ix in indices {
+            unsafe { es.push_unchecked(&mut *base.add(ix)) };
+        }
+        Ok(es)
+    }
+
+    fn scatter_accumulate<F: FnMut(&mut T, usize)>(
+        &mut self,
+        indices: &[usize],
+        mut f: F,
+    ) -> Result<(), MutElemsError> {
+        check_bounds(indices, self.len())?;
+        for (position, &ix) in indices.iter().enumerate() {
+            f(&mut self[ix], position);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_split<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<(Vec<&'a mut T>, SplitRest<'a, T>), MutElemsError> {
+        let length = self.len();
+        check_indices_slice_in(indices, length)?;
+
+        let mut selected = indices.to_vec();
+        selected.sort_unstable();
+
+        // Safety: Indices have been checked for inequality and
+        // bounds, as with `mut_elems()`. The rest view only
+        // gives out references to unchosen indices, so never
+        // one aliasing a chosen element. All references are
+        // derived from a single base pointer, as with
+        // `mut_elems_unchecked()`.
+        let base = self.as_mut_ptr();
+        let es = indices
+            .iter()
+            .map(|&ix| unsafe { &mut *base.add(ix) })
+            .collect();
+        let rest = SplitRest {
+            base,
+            length,
+            selected,
+            phantom: PhantomData,
+        };
+        Ok((es, rest))
+    }
+
+    fn mut_first<const N: usize>(&mut self) -> Result<[&mut T; N], MutElemsError> {
+        let length = self.len();
+        match self.first_chunk_mut::<N>() {
+            Some(chunk) => Ok(chunk.each_mut()),
+            None => Err(LengthMismatch {
+                expected: N,
+                actual: length,
+            }),
+        }
+    }
+
+    fn mut_last<const N: usize>(&mut self) -> Result<[&mut T; N], MutElemsError> {
+        let length = self.len();
+        match self.last_chunk_mut::<N>() {
+            Some(chunk) => Ok(chunk.each_mut()),
+            None => Err(LengthMismatch {
+                expected: N,
+                actual: length,
+            }),
+        }
+    }
+
+    fn write_elems<const N: usize>(
+        &mut self,
+        indices: &[usize; N],
+        values: [T; N],
+    ) -> Result<(), MutElemsError> {
+        let es = self.mut_elems(indices)?;
+        for (e, value) in es.into_iter().zip(values) {
+            *e = value;
+        }
+        Ok(())
+    }
+
+    fn permute_elems<const N: usize>(
+        &mut self,
+        indices: &[usize; N],
+        perm: &[usize; N],
+    ) -> Result<(), MutElemsError> {
+        // A permutation of `0..N` is `N` distinct indices less
+        // than `N`.
+        match check_indices_in(perm, N) {
+            Err(IndexBound { position, .. })
+            | Err(IndicesOverlap {
+                second: position, ..
+            }) => return Err(InvalidPermutation { position }),
+            result => result?,
+        }
+        let mut es = self.mut_elems(indices)?;
+
+        // Follow each cycle of the permutation, swapping each
+        // element into place from the next one along.
+        let mut done = [false; N];
+        for start in 0..N {
+            let mut k = start;
+            while !done[k] {
+                done[k] = true;
+                let next = perm[k];
+                if next == start {
+                    break;
+                }
+                // `next` is not yet done, so differs from `k`.
+                debug_assert_ne!(k, next);
+                let (low, high) = (k.min(next), k.max(next));
+                let (front, back) = es.split_at_mut(high);
+                core::mem::swap(&mut *front[low], &mut *back[0]);
+                k = next;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_diagnostic<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsDiagnostic> {
+        self.mut_elems(indices).map_err(|error| MutElemsDiagnostic {
+            error,
+            indices: indices.to_vec(),
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_distinct<'a>(
+        &'a mut self,
+        indices: &DistinctIndices,
+    ) -> Result<Vec<&'a mut T>, MutElemsError> {
+        check_bounds(indices.indices(), self.len())?;
+
+        // Safety: Indices were checked for inequality when
+        // `indices` was made, and bounds have been checked.
+        // All references are derived from a single base
+        // pointer, as with `mut_elems_unchecked()`.
+        let base = self.as_mut_ptr();
+        Ok(indices
+            .indices()
+            .iter()
+            .map(|&ix| unsafe { &mut *base.add(ix) })
+            .collect())
+    }
+
+    fn mut_elems_monotonic<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        let descending = N >= 2 && indices[0] > indices[1];
+        for (i, w) in indices.windows(2).enumerate() {
+            if w[0] == w[1] {
+                return Err(IndicesOverlap {
+                    first: i,
+                    second: i + 1,
+                    index: w[1],
+                });
+            }
+            if (w[0] > w[1]) != descending {
+                return Err(IndicesUnsorted { position: i + 1 });
+            }
+        }
+        // Only the largest index, at one end, can be out of
+        // bounds.
+        let position = if descending { 0 } else { N.saturating_sub(1) };
+        if let Some(&index) = indices.get(position) {
+            let length = self.len();
+            if index >= length {
+                return Err(IndexBound {
+                    position,
+                    index,
+                    length,
+                });
+            }
+        }
+
+        // Safety: Indices are strictly monotonic, so they must
+        // indicate unique locations, and the largest is in
+        // bounds.
+        Ok(unsafe { self.mut_elems_unchecked(indices) })
+    }
+
+    fn mut_elems_sort_in_place<'a, const N: usize>(
+        &'a mut self,
+        indices: &mut [usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        indices.sort_unstable();
+        self.mut_elems_sorted(indices)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mut_elems_with_scratch<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+        scratch: &mut Vec<(usize, usize)>,
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        let length = self.len();
+        // Only the sort needs a buffer; leave the rest to the
+        // usual check.
+        if N > LINEAR_SCAN_LIMIT && bitset_low(indices, length).is_none() {
+            trace_strategy!("sort in scratch", N, length);
+            scratch.clear();
+            scratch.resize(N, (0, 0));
+            check_indices_sort(indices, length, scratch)?;
+        } else {
+            check_indices_in(indices, length)?;
+        }
+
+        // Safety: Indices have been checked for inequality and
+        // bounds, as with `mut_elems()`.
+        Ok(unsafe { self.mut_elems_unchecked(indices) })
+    }
+
+    unsafe fn mut_elems_dbg<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> [&'a mut T; N] {
+        #[cfg(debug_assertions)]
+        if let Err(error) = check_indices_in(indices, self.len()) {
+            panic!("mut_elems_dbg(): {error} (indices {indices:?})");
+        }
+
+        // Safety: The caller guarantees that the indices are
+        // distinct and in bounds, and in debug builds this has
+        // been checked.
+        unsafe { self.mut_elems_unchecked(indices) }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn iter_mut_except(
+        &mut self,
+        excluded: &[usize],
+    ) -> Result<IterMutExcept<'_, T>, MutElemsError> {
+        check_bounds(excluded, self.len())?;
+        let mut skip = excluded.to_vec();
+        skip.sort_unstable();
+        skip.dedup();
+        Ok(IterMutExcept {
+            inner: self.iter_mut().enumerate(),
+            skip: skip.into_iter().peekable(),
+        })
+    }
+
+    fn mut_elems_by<const N: usize, F: FnOnce(&[T]) -> [usize; N]>(
+        &mut self,
+        select: F,
+    ) -> Result<[&mut T; N], MutElemsError> {
+        let indices = select(self);
+        self.mut_elems(&indices)
+    }
+
+    fn mut_elems_transact<const N: usize, E, F>(
+        &mut self,
+        indices: &[usize; N],
+        f: F,
+    ) -> Result<Result<(), E>, MutElemsError>
+    where
+        T: Clone,
+        F: FnOnce([&mut T; N]) -> Result<(), E>,
+    {
+        let mut es = self.mut_elems(indices)?;
+        let snapshot = es.each_ref().map(|e| T::clone(e));
+        let result = f(es.each_mut().map(|e| &mut **e));
+        if result.is_err() {
+            for (e, saved) in es.into_iter().zip(snapshot) {
+                *e = saved;
+            }
+        }
+        Ok(result)
+    }
+
+    fn mut_k_smallest<const K: usize>(&mut self) -> Result<[&mut T; K], MutElemsError>
+    where
+        T: Ord,
+    {
+        let indices = select_k(self, |a, b| a < b)?;
+        // Safety: `select_k()` returns distinct in-bounds
+        // positions.
+        Ok(unsafe { self.mut_elems_unchecked(&indices) })
+    }
+
+    fn mut_k_largest<const K: usize>(&mut self) -> Result<[&mut T; K], MutElemsError>
+    where
+        T: Ord,
+    {
+        let indices = select_k(self, |a, b| a > b)?;
+        // Safety: `select_k()` returns distinct in-bounds
+        // positions.
+        Ok(unsafe { self.mut_elems_unchecked(&indices) })
+    }
+
+    fn mut_elems_and_rest_ref<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<([&'a mut T; N], RestRef<'a, T, N>), MutElemsError> {
+        let length = self.len();
+        check_indices_in(indices, length)?;
+
+        let mut selected = *indices;
+        selected.sort_unstable();
+
+        // Safety: Indices have been checked for inequality and
+        // bounds, as with `mut_elems()`. The rest view only
+        // gives out references to unchosen indices, as with
+        // `mut_elems_split()`. All references are derived
+        // from a single base pointer, as with
+        // `mut_elems_unchecked()`.
+        let base = self.as_mut_ptr();
+        let es = indices.map(|ix| unsafe { &mut *base.add(ix) });
+        let rest = RestView {
+            base,
+            length,
+            selected,
+            phantom: PhantomData,
+        };
+        Ok((es, rest))
+    }
+}
+
+/// Generate methods of an impl of [MutElemsExt] that
+/// forward to the slice impl, from their signatures. Generic
+/// parameters and `where` clauses are given in brackets.
+macro_rules! forward_to_slice {
+    () => {};
+    (
+        $(#[$attr:meta])*
+        fn $name:ident [$($gen:tt)*]
+            (&$($lt:lifetime)? mut self $(, $arg:ident: $ty:ty)* $(,)?)
+            -> $ret:ty $(where [$($bound:tt)*])?;
+        $($rest:tt)*
+    ) => {
+        $(#[$attr])*
+        fn $name<$($gen)*>(&$($lt)? mut self $(, $arg: $ty)*) -> $ret $(where $($bound)*)? {
+            <[T] as MutElemsExt<T>>::$name(self.as_mut_slice() $(, $arg)*)
+        }
+        forward_to_slice! { $($rest)* }
+    };
+    (
+        $(#[$attr:meta])*
+        unsafe fn $name:ident [$($gen:tt)*]
+            (&$($lt:lifetime)? mut self $(, $arg:ident: $ty:ty)* $(,)?)
+            -> $ret:ty $(where [$($bound:tt)*])?;
+        $($rest:tt)*
+    ) => {
+        $(#[$attr])*
+        unsafe fn $name<$($gen)*>(&$($lt)? mut self $(, $arg: $ty)*) -> $ret $(where $($bound)*)? {
+            // Safety: The caller upholds the same contract for
+            // the slice as for the array.
+            unsafe { <[T] as MutElemsExt<T>>::$name(self.as_mut_slice() $(, $arg)*) }
+        }
+        forward_to_slice! { $($rest)* }
+    };
+}
+
+// Arrays bounds-check against their static length `M`, and
+// otherwise defer to the slice implementation.
+impl<const M: usize, T> MutElemsExt<T> for [T; M] {
+    fn mut_elems<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        // The target length is known to be `M`.
+        check_indices_in(indices, M)?;
+
+        // Safety: Indices have been checked for inequality and
+        // bounds.
+        Ok(unsafe { self.mut_elems_unchecked(indices) })
+    }
+
+    forward_to_slice! {
+        #[cfg(feature = "alloc")]
+        fn mut_elems_slice['a](
+            &'a mut self,
+            indices: &[usize],
+        ) -> Result<Vec<&'a mut T>, MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_report_all['a, const N: usize](
+            &'a mut self,
+            indices: &[usize; N],
+        ) -> Result<[&'a mut T; N], MutElemsError>;
+        fn get_disjoint_mut[const N: usize](
+            &mut self,
+            indices: [usize; N],
+        ) -> Result<[&mut T; N], MutElemsError>;
+        unsafe fn mut_elems_unchecked['a, const N: usize](
+            &'a mut self,
+            indices: &[usize; N],
+        ) -> [&'a mut T; N];
+        fn mut_ranges['a, const N: usize](
+            &'a mut self,
+            ranges: &[Range<usize>; N],
+        ) -> Result<[&'a mut [T]; N], MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_iter[I](
+            &mut self,
+            indices: I,
+        ) -> MutElemsIter<'_, T, I::IntoIter> where [I: IntoIterator<Item = usize>];
+        fn mut_elems_sorted['a, const N: usize](
+            &'a mut self,
+            indices: &[usize; N],
+        ) -> Result<[&'a mut T; N], MutElemsError>;
+        fn mut_elems_generic['a, I: ElemIndex, const N: usize](
+            &'a mut self,
+            indices: &[I; N],
+        ) -> Result<[&'a mut T; N], MutElemsError>;
+        fn mut_elems_signed['a, const N: usize](
+            &'a mut self,
+            indices: &[isize; N],
+        ) -> Result<[&'a mut T; N], MutElemsError>;
+        fn mut_elems_opt['a, const N: usize](
+            &'a mut self,
+            indices: &[usize; N],
+        ) -> Option<[&'a mut T; N]>;
+        fn mut_elems_with[const N: usize, R](
+            &mut self,
+            indices: &[usize; N],
+            f: impl FnOnce([&mut T; N]) -> R,
+        ) -> Result<R, MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_partial['a](
+            &'a mut self,
+            indices: &[usize],
+        ) -> (Vec<(usize, &'a mut T)>, Vec<MutElemsError>);
+        #[cfg(feature = "std")]
+        fn mut_elems_map['a](
+            &'a mut self,
+            indices: &[usize],
+        ) -> Result<HashMap<usize, &'a mut T>, MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_dedup['a](
+            &'a mut self,
+            indices: &[usize],
+        ) -> Result<Vec<&'a mut T>, MutElemsError>;
+        fn mut_indexed['a, I: MutIndexTuple<'a, T>](
+            &'a mut self,
+            indices: I,
+        ) -> Result<I::Output, MutElemsError>;
+        fn chunks_disjoint_mut[const N: usize](&mut self) -> [&mut [T]; N];
+        fn as_mut_elems_array[const N: usize](&mut self) -> Result<[&mut T; N], MutElemsError>;
+        fn try_as_mut_elems[const N: usize](&mut self) -> Result<[&mut T; N], MutElemsError>;
+        fn mut_elems_cross['a, 'b, const N: usize, const K: usize](
+            &'a mut self,
+            indices: &[usize; N],
+            other: &'b mut [T],
+            other_indices: &[usize; K],
+        ) -> Result<([&'a mut T; N], [&'b mut T; K]), MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_verbose['a, const N: usize](
+            &'a mut self,
+            indices: &[usize; N],
+        ) -> Result<[&'a mut T; N], MutElemsError>;
+        fn mut_elems_rest['a, const N: usize](
+            &'a mut self,
+            indices: &[usize; N],
+        ) -> Result<([&'a mut T; N], MutElemsRest<'a, T, N>), MutElemsError>;
+        fn mut_elems_wrapped['a, const N: usize](
+            &'a mut self,
+            indices: &[usize; N],
+        ) -> Result<MutElems<'a, T, N>, MutElemsError>;
+        unsafe fn mut_elems_checked_overlap_only['a, const N: usize](
+            &'a mut self,
+            indices: &[usize; N],
+        ) -> Result<[&'a mut T; N], MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_ordered['a](
+            &'a mut self,
+            indices: &[usize],
+        ) -> Result<(Vec<&'a mut T>, Vec<usize>), MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_masked['a](
+            &'a mut self,
+            mask: &[bool],
+        ) -> Result<Vec<&'a mut T>, MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn windows_disjoint_mut['a](
+            &'a mut self,
+            starts: &[usize],
+            width: usize,
+        ) -> Result<Vec<&'a mut [T]>, MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_boxed['a](
+            &'a mut self,
+            indices: &[usize],
+        ) -> Result<Box<[&'a mut T]>, MutElemsError>;
+        fn mut_elems_tagged['a, const N: usize](
+            &'a mut self,
+            indices: &[usize; N],
+        ) -> Result<[(usize, &'a mut T); N], MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_stride[](
+            &mut self,
+            start: usize,
+            step: usize,
+            count: usize,
+        ) -> Result<Vec<&mut T>, MutElemsError>;
+        fn mut_swap_if[](
+            &mut self,
+            i: usize,
+            j: usize,
+            pred: impl FnOnce(&T, &T) -> bool,
+        ) -> Result<bool, MutElemsError>;
+        fn mut_elems_grouped['a, const G: usize, const N: usize](
+            &'a mut self,
+            groups: &[[usize; N]; G],
+        ) -> Result<[[&'a mut T; N]; G], MutElemsError>;
+        #[cfg(feature = "arrayvec")]
+        fn mut_elems_arrayvec['a, const MAX: usize](
+            &'a mut self,
+            indices: &[usize],
+        ) -> Result<arrayvec::ArrayVec<&'a mut T, MAX>, MutElemsError>;
+        fn scatter_accumulate[F: FnMut(&mut T, usize)](
+            &mut self,
+            indices: &[usize],
+            f: F,
+        ) -> Result<(), MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_split['a](
+            &'a mut self,
+            indices: &[usize],
+        ) -> Result<(Vec<&'a mut T>, SplitRest<'a, T>), MutElemsError>;
+        fn mut_first[const K: usize](&mut self) -> Result<[&mut T; K], MutElemsError>;
+        fn mut_last[const K: usize](&mut self) -> Result<[&mut T; K], MutElemsError>;
+        fn write_elems[const N: usize](
+            &mut self,
+            indices: &[usize; N],
+            values: [T; N],
+        ) -> Result<(), MutElemsError>;
+        fn permute_elems[const N: usize](
+            &mut self,
+            indices: &[usize; N],
+            perm: &[usize; N],
+        ) -> Result<(), MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_diagnostic['a, const N: usize](
+            &'a mut self,
+            indices: &[usize; N],
+        ) -> Result<[&'a mut T; N], MutElemsDiagnostic>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_distinct['a](
+            &'a mut self,
+            indices: &DistinctIndices,
+        ) -> Result<Vec<&'a mut T>, MutElemsError>;
+        fn mut_elems_monotonic['a, const N: usize](
+            &'a mut self,
+            indices: &[usize; N],
+        ) -> Result<[&'a mut T; N], MutElemsError>;
+        fn mut_elems_sort_in_place['a, const N: usize](
+            &'a mut self,
+            indices: &mut [usize; N],
+        ) -> Result<[&'a mut T; N], MutElemsError>;
+        #[cfg(feature = "alloc")]
+        fn mut_elems_with_scratch['a, const K: usize](
+            &'a mut self,
+            indices: &[usize; K],
+            scratch: &mut Vec<(usize, usize)>,
+        ) -> Result<[&'a mut T; K], MutElemsError>;
+        unsafe fn mut_elems_dbg['a, const N: usize](
+            &'a mut self,
+            indices: &[usize; N],
+        ) -> [&'a mut T; N];
+        #[cfg(feature = "alloc")]
+        fn iter_mut_except[](
+            &mut self,
+            excluded: &[usize],
+        ) -> Result<IterMutExcept<'_, T>, MutElemsError>;
+        fn mut_elems_by[const K: usize, F: FnOnce(&[T]) -> [usize; K]](
+            &mut self,
+            select: F,
+        ) -> Result<[&mut T; K], MutElemsError>;
+        fn mut_elems_transact[const K: usize, E, F](
+            &mut self,
+            indices: &[usize; K],
+            f: F) -> Result<Result<(),
+            E>, MutElemsError> where [T: Clone, F: FnOnce([&mut T; K],
+        ) -> Result<(), E>];
+        fn mut_k_smallest[const K: usize](
+            &mut self,
+        ) -> Result<[&mut T; K], MutElemsError> where [T: Ord];
+        fn mut_k_largest[const K: usize](
+            &mut self,
+        ) -> Result<[&mut T; K], MutElemsError> where [T: Ord];
+        fn mut_elems_and_rest_ref['a, const K: usize](
+            &'a mut self,
+            indices: &[usize; K],
+        ) -> Result<([&'a mut T; K], RestRef<'a, T, K>), MutElemsError>;
+    }
+}
+
+impl<const N: usize, T> AsMutElemsExt<N, T> for [T; N] {
+    fn as_mut_elems(&mut self) -> [&mut T; N] {
+        // Safety: `from_fn()` guarantees that indices `i`
+        // are in-bounds and unique. All references are derived
+        // from a single base pointer, as with
+        // `MutElemsExt::mut_elems_unchecked()`.
+        let base = self.as_mut_ptr();
+        core::array::from_fn(|i| unsafe { &mut *base.add(i) })
+    }
+}
+
+// Slices get a direct implementation, so that calling these
+// methods on a `&mut [T]` borrows the slice itself rather
+// than going through `AsMut` on the reference. This does
+// not overlap the blanket implementation below, which only
+// covers sized receivers.
+#[cfg(feature = "alloc")]
+impl<T> AsMutElemsVecExt<T> for [T] {
+    fn as_mut_elems_vec(&mut self) -> Vec<&mut T> {
+        // Iteration guarantees that elements are in-bounds and
+        // unique.
+        self.iter_mut().collect()
+    }
+
+    #[cfg(feature = "smallvec")]
+    fn as_mut_elems_smallvec<const INLINE: usize>(
+        &mut self,
+    ) -> smallvec::SmallVec<[&mut T; INLINE]> {
+        // Iteration guarantees that elements are in-bounds and
+        // unique.
+        self.iter_mut().collect()
+    }
+
+    fn as_mut_elems_vec_indexed(
+        &mut self,
+        indices: &[usize],
+    ) -> Result<Vec<&mut T>, MutElemsError> {
+        self.mut_elems_slice(indices)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, V> AsMutElemsVecExt<T> for V
+where
+    V: AsMut<[T]>,
+{
+    fn as_mut_elems_vec(&mut self) -> Vec<&mut T> {
+        // Iteration guarantees that elements are in-bounds and
+        // unique.
+        self.as_mut().iter_mut().collect()
+    }
+
+    #[cfg(feature = "smallvec")]
+    fn as_mut_elems_smallvec<const INLINE: usize>(
+        &mut self,
+    ) -> smallvec::SmallVec<[&mut T; INLINE]> {
+        // Iteration guarantees that elements are in-bounds and
+        // unique.
+        self.as_mut().iter_mut().collect()
+    }
+
+    fn as_mut_elems_vec_indexed(
+        &mut self,
+        indices: &[usize],
+    ) -> Result<Vec<&mut T>, MutElemsError> {
+        self.as_mut().mut_elems_slice(indices)
+    }
+}
+
+/// Iterator returned by [MutElemsExt::mut_elems_iter].
+#[cfg(feature = "alloc")]
+pub struct MutElemsIter<'a, T, I> {
+    base: *mut T,
+    length: usize,
+    indices: I,
+    position: usize,
+    // Map from each index yielded so far to its position.
+    seen: BTreeMap<usize, usize>,
+    phantom: PhantomData<&'a mut [T]>,
+}
+
+// Safety: A `MutElemsIter` is an exclusive borrow of a slice,
+// like `core::slice::IterMut`.
+#[cfg(feature = "alloc")]
+unsafe impl<T: Send, I: Send> Send for MutElemsIter<'_, T, I> {}
+#[cfg(feature = "alloc")]
+unsafe impl<T: Sync, I: Sync> Sync for MutElemsIter<'_, T, I> {}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, I> Iterator for MutElemsIter<'a, T, I>
+where
+    I: Iterator<Item = usize>,
+{
+    type Item = Result<&'a mut T, MutElemsError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.indices.next()?;
+        let position = self.position;
+        self.position += 1;
+
+        if index >= self.length {
+            return Some(Err(IndexBound {
+                position,
+                index,
+                length: self.length,
+            }));
+        }
+        if let Some(&first) = self.seen.get(&index) {
+            return Some(Err(IndicesOverlap {
+                first,
+                second: position,
+                index,
+            }));
+        }
+        self.seen.insert(index, position);
+
+        // Safety: The index is in bounds, and has not been
+        // yielded before, so the reference is valid and does
+        // not alias any other reference this iterator yields.
+        // The iterator holds the borrow of the slice for `'a`.
+        Some(Ok(unsafe { &mut *self.base.add(index) }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+/// Mutable references to selected elements, as returned by
+/// [MutElemsExt::mut_elems_wrapped].
+///
+/// Indexing gives the referenced elements themselves, so
+/// that `es[i]` is the element behind the `i`-th reference.
+/// Dereferencing gives the slice of references.
+#[derive(Debug)]
+pub struct MutElems<'a, T, const N: usize>(pub [&'a mut T; N]);
+
+impl<'a, T, const N: usize> MutElems<'a, T, N> {
+    /// Return the array of references.
+    pub fn into_inner(self) -> [&'a mut T; N] {
+        self.0
+    }
+
+    /// Return the number of references, which is `N`.
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Return `true` if there are no references, that is if
+    /// `N` is 0.
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Split the references into those to elements for which
+    /// `pred` holds and those to the rest, each in their
+    /// original order. This consumes `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = [5u8, 1, 8, 2];
+    /// let es = v.mut_elems_wrapped(&[0, 1, 2, 3]).unwrap();
+    /// let (large, small) = es.partition(|&e| e > 4);
+    /// large.into_iter().for_each(|e| *e *= 10);
+    /// small.into_iter().for_each(|e| *e = 0);
+    /// assert_eq!([50, 0, 80, 0], v);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn partition<F: Fn(&T) -> bool>(self, pred: F) -> (Vec<&'a mut T>, Vec<&'a mut T>) {
+        self.0.into_iter().partition(|e| pred(e))
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for MutElems<'a, T, N> {
+    type Item = &'a mut T;
+    type IntoIter = core::array::IntoIter<&'a mut T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T, const N: usize> Index<usize> for MutElems<'_, T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.0[index]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for MutElems<'_, T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.0[index]
+    }
+}
+
+impl<'a, T, const N: usize> Deref for MutElems<'a, T, N> {
+    type Target = [&'a mut T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> DerefMut for MutElems<'_, T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// A set of indices checked once for distinctness, for
+/// repeated use with different slices. Distinctness does not
+/// depend on the slice, so [MutElemsRequest::apply] need only
+/// check bounds.
+///
+/// # Examples
+///
+/// ```
+/// use mut_elems::*;
+///
+/// let request = MutElemsRequest::new([2, 0]).unwrap();
+/// let mut rows = [[1u8, 2, 3], [4, 5, 6]];
+/// for row in &mut rows {
+///     let [a, b] = request.apply(row).unwrap();
+///     core::mem::swap(a, b);
+/// }
+/// assert_eq!([[3, 2, 1], [6, 5, 4]], rows);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MutElemsRequest<const N: usize> {
+    indices: [usize; N],
+}
+
+impl<const N: usize> MutElemsRequest<N> {
+    /// Make a request for the elements at `indices`.
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::IndicesOverlap] if any pair
+    /// of indices is identical.
+    pub fn new(indices: [usize; N]) -> Result<Self, MutElemsError> {
+        check_distinct(&indices)?;
+        Ok(MutElemsRequest { indices })
+    }
+
+    /// The requested indices.
+    pub fn indices(&self) -> &[usize; N] {
+        &self.indices
+    }
+
+    /// Return mutable references to elements of `slice` at
+    /// each of the requested indices, as with
+    /// [MutElemsExt::mut_elems].
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::IndexBound] if any of the
+    /// indices are out of bounds of `slice`.
+    pub fn apply<'a, T>(&self, slice: &'a mut [T]) -> Result<[&'a mut T; N], MutElemsError> {
+        check_bounds(&self.indices, slice.len())?;
+
+        // Safety: Indices were checked for inequality when
+        // the request was made, and bounds have been checked.
+        Ok(unsafe { slice.mut_elems_unchecked(&self.indices) })
+    }
+}
+
+/// A runtime-length set of indices checked once for
+/// distinctness, as [MutElemsRequest] is for a fixed count.
+/// [MutElemsExt::mut_elems_distinct] then need only check
+/// bounds.
+///
+/// # Examples
+///
+/// ```
+/// use mut_elems::*;
+///
+/// let indices = DistinctIndices::new(&[2, 0]).unwrap();
+/// let mut rows = [[1u8, 2, 3], [4, 5, 6]];
+/// for row in &mut rows {
+///     for e in row.mut_elems_distinct(&indices).unwrap() {
+///         *e *= 10;
+///     }
+/// }
+/// assert_eq!([[10, 2, 30], [40, 5, 60]], rows);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DistinctIndices {
+    indices: Box<[usize]>,
+}
+
+#[cfg(feature = "alloc")]
+impl DistinctIndices {
+    /// Check that `indices` are distinct, and keep a copy.
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::IndicesOverlap] if any pair
+    /// of indices is identical.
+    pub fn new(indices: &[usize]) -> Result<Self, MutElemsError> {
+        // As in `check_distinct()`, check indices equal to
+        // `usize::MAX` in full.
+        match check_indices_slice_in(indices, usize::MAX) {
+            Err(IndexBound { .. }) => {
+                let mut pairs: Vec<(usize, usize)> = indices.iter().copied().zip(0..).collect();
+                find_overlap(&mut pairs)?;
+            }
+            result => result?,
+        }
+        Ok(DistinctIndices {
+            indices: indices.into(),
+        })
+    }
+
+    /// The indices, in their original order.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<&[usize]> for DistinctIndices {
+    type Error = MutElemsError;
+
+    fn try_from(indices: &[usize]) -> Result<Self, MutElemsError> {
+        DistinctIndices::new(indices)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> TryFrom<[usize; N]> for DistinctIndices {
+    type Error = MutElemsError;
+
+    fn try_from(indices: [usize; N]) -> Result<Self, MutElemsError> {
+        check_distinct(&indices)?;
+        Ok(DistinctIndices {
+            indices: Box::new(indices),
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> From<MutElemsRequest<N>> for DistinctIndices {
+    fn from(request: MutElemsRequest<N>) -> Self {
+        DistinctIndices {
+            indices: Box::new(request.indices),
+        }
+    }
+}
+
+/// Iterator over the elements not chosen by
+/// [MutElemsExt::mut_elems_rest].
+pub struct MutElemsRest<'a, T, const N: usize> {
+    base: *mut T,
+    length: usize,
+    position: usize,
+    // The chosen indices in ascending order, and how many of
+    // them have been passed.
+    skip: [usize; N],
+    next_skip: usize,
+    phantom: PhantomData<&'a mut [T]>,
+}
+
+// Safety: A `MutElemsRest` is an exclusive borrow of part of
+// a slice, like `core::slice::IterMut`.
+unsafe impl<T: Send, const N: usize> Send for MutElemsRest<'_, T, N> {}
+unsafe impl<T: Sync, const N: usize> Sync for MutElemsRest<'_, T, N> {}
+
+impl<'a, T, const N: usize> Iterator for MutElemsRest<'a, T, N> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position < self.length {
+            let position = self.position;
+            self.position += 1;
+            if self.next_skip < N && self.skip[self.next_skip] == position {
+                self.next_skip += 1;
+                continue;
+            }
+
+            // Safety: The position is in bounds, is not one of
+            // the chosen indices, and is never yielded twice.
+            // The iterator holds the borrow of the slice for
+            // `'a`.
+            return Some(unsafe { &mut *self.base.add(position) });
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Every chosen index not yet passed is at or after
+        // the current position.
+        let n = self.length - self.position - (N - self.next_skip);
+        (n, Some(n))
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for MutElemsRest<'_, T, N> {}
+
+/// Read-only view of the elements not chosen by
+/// [MutElemsExt::mut_elems_and_rest_ref] or
+/// [MutElemsExt::mut_elems_split]. `S` holds the chosen
+/// indices: an array or a `Vec`, respectively.
+pub struct RestView<'a, T, S> {
+    base: *mut T,
+    length: usize,
+    // The chosen indices in ascending order.
+    selected: S,
+    phantom: PhantomData<&'a [T]>,
+}
+
+/// Read-only view of the elements not chosen by
+/// [MutElemsExt::mut_elems_and_rest_ref].
+pub type RestRef<'a, T, const N: usize> = RestView<'a, T, [usize; N]>;
+
+/// Read-only view of the elements not chosen by
+/// [MutElemsExt::mut_elems_split].
+#[cfg(feature = "alloc")]
+pub type SplitRest<'a, T> = RestView<'a, T, Vec<usize>>;
+
+// Safety: A `RestView` is a shared borrow of part of a
+// slice, like `&[T]`.
+unsafe impl<T: Sync, S: Send> Send for RestView<'_, T, S> {}
+unsafe impl<T: Sync, S: Sync> Sync for RestView<'_, T, S> {}
+
+impl<'a, T, S: AsRef<[usize]>> RestView<'a, T, S> {
+    /// Length of the whole target, chosen elements included.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Whether the whole target is empty.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Whether the element at `index` was chosen, and so
+    /// cannot be read through this view.
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selected.as_ref().binary_search(&index).is_ok()
+    }
+
+    /// Return a reference to the element at `index`, or
+    /// `None` if `index` is out of bounds or was chosen.
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        if index >= self.length || self.is_selected(index) {
+            return None;
+        }
+        // Safety: The index is in bounds and not one of the
+        // chosen indices. The view holds the borrow of the
+        // slice for `'a`.
+        Some(unsafe { &*self.base.add(index) })
+    }
+}
+
+impl<T, S: AsRef<[usize]>> Index<usize> for RestView<'_, T, S> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or was chosen.
+    fn index(&self, index: usize) -> &T {
+        match self.get(index) {
+            Some(e) => e,
+            None if index >= self.length => {
+                panic!("index {index} out of bounds for length {}", self.length)
+            }
+            None => panic!("index {index} is mutably borrowed"),
+        }
+    }
+}
+
+/// Iterator over the elements not excluded by
+/// [MutElemsExt::iter_mut_except], with their indices.
+#[cfg(feature = "alloc")]
+pub struct IterMutExcept<'a, T> {
+    inner: core::iter::Enumerate<core::slice::IterMut<'a, T>>,
+    // The excluded indices in ascending order, without
+    // repeats, from the next one not yet passed.
+    skip: core::iter::Peekable<alloc::vec::IntoIter<usize>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> Iterator for IterMutExcept<'a, T> {
+    type Item = (usize, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (i, e) in self.inner.by_ref() {
+            if self.skip.next_if_eq(&i).is_none() {
+                return Some((i, e));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Every excluded index not yet passed is still ahead.
+        let n = self.inner.len() - self.skip.len();
+        (n, Some(n))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> ExactSizeIterator for IterMutExcept<'_, T> {}
+
+/// Mutable references to several rows of a matrix stored
+/// as a slice or `Vec` of row `Vec`s.
+#[cfg(feature = "alloc")]
+pub trait MutRowsExt<T> {
+    /// Return mutable references to the rows of `self` at
+    /// each of the row indices given by `rows`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the row indices are out
+    /// of bounds of the number of rows, or if any pair of row
+    /// indices is identical.
+    fn mut_rows<'a, const N: usize>(
+        &'a mut self,
+        rows: &[usize; N],
+    ) -> Result<[&'a mut Vec<T>; N], MutElemsError>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T> MutRowsExt<T> for [Vec<T>] {
+    fn mut_rows<'a, const N: usize>(
+        &'a mut self,
+        rows: &[usize; N],
+    ) -> Result<[&'a mut Vec<T>; N], MutElemsError> {
+        self.mut_elems(rows)
+    }
+}
+
+/// Mutable references to several elements of a
+/// two-dimensional array, such as an image buffer stored as
+/// `[[u8; W]; H]`.
+pub trait MutPixelsExt<T> {
+    /// Return mutable references to the elements of `self`
+    /// at each of the `(row, col)` coordinates given by
+    /// `coords`.
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::Index2DBound] if either
+    /// coordinate of a pair is out of bounds, or
+    /// [MutElemsError::IndicesOverlap] if any pair of
+    /// coordinates is repeated. In the latter case `index` is
+    /// the flattened offset `row * cols + col`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero-sized and the array has more
+    /// than `usize::MAX` elements in total.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut image = [[0u8; 3]; 2];
+    /// let [p, q] = image.mut_pixels(&[(0, 2), (1, 0)]).unwrap();
+    /// *p = 7;
+    /// *q = 9;
+    /// assert_eq!([[0, 0, 7], [9, 0, 0]], image);
+    /// ```
+    fn mut_pixels<const N: usize>(
+        &mut self,
+        coords: &[(usize, usize); N],
+    ) -> Result<[&mut T; N], MutElemsError>;
+}
+
+impl<const R: usize, const C: usize, T> MutPixelsExt<T> for [[T; C]; R] {
+    fn mut_pixels<const N: usize>(
+        &mut self,
+        coords: &[(usize, usize); N],
+    ) -> Result<[&mut T; N], MutElemsError> {
+        let mut indices = [0; N];
+        for (position, (&(row, col), index)) in coords.iter().zip(&mut indices).enumerate() {
+            if row >= R || col >= C {
+                return Err(Index2DBound {
+                    position,
+                    row,
+                    col,
+                    rows: R,
+                    cols: C,
+                });
+            }
+            // Less than `R * C`, which only overflows when the
+            // flattening below panics anyway.
+            *index = row * C + col;
+        }
+        self.as_flattened_mut().mut_elems(&indices)
+    }
+}
+
+/// Length and capacity of a `Vec`, as returned alongside
+/// element references by
+/// [MutElemsVecExt::mut_elems_and_metadata].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VecMetadata {
+    /// The length of the `Vec`.
+    pub len: usize,
+    /// The capacity of the `Vec`.
+    pub capacity: usize,
+}
+
+/// Mutable references to several elements of a `Vec`,
+/// together with its metadata.
+///
+/// References returned by [MutElemsExt::mut_elems] on a `Vec`
+/// hold a mutable borrow of the whole `Vec`, so while they
+/// are live not even `len()` or `capacity()` can be called:
+///
+/// ```compile_fail
+/// use mut_elems::*;
+///
+/// let mut v = vec![1u8, 2, 3];
+/// let [a] = v.mut_elems(&[0]).unwrap();
+/// *a = v.len() as u8;
+/// ```
+///
+/// The metadata cannot change while the references are live,
+/// so a copy taken at the same time stays accurate.
+#[cfg(feature = "alloc")]
+pub trait MutElemsVecExt<T> {
+    /// Return mutable references to elements of `self` at
+    /// each of the index positions given by `indices`, as
+    /// with [MutElemsExt::mut_elems], together with the
+    /// length and capacity of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut v = vec![1u8, 2, 3];
+    /// let ([a], meta) = v.mut_elems_and_metadata(&[0]).unwrap();
+    /// *a = meta.len as u8;
+    /// assert_eq!(vec![3, 2, 3], v);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems].
+    fn mut_elems_and_metadata<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<([&'a mut T; N], VecMetadata), MutElemsError>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T> MutElemsVecExt<T> for Vec<T> {
+    fn mut_elems_and_metadata<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<([&'a mut T; N], VecMetadata), MutElemsError> {
+        let meta = VecMetadata {
+            len: self.len(),
+            capacity: self.capacity(),
+        };
+        let es = self.as_mut_slice().mut_elems(indices)?;
+        Ok((es, meta))
+    }
+}
+
+/// Optimal-size sorting networks for arrays of up to 8
+/// elements, indexed by length, as lists of compare-exchanges.
+const SORT_NETWORKS: [&[(usize, usize)]; 9] = [
+    &[],
+    &[],
+    &[(0, 1)],
+    &[(0, 2), (0, 1), (1, 2)],
+    &[(0, 2), (1, 3), (0, 1), (2, 3), (1, 2)],
+    &[
+        (0, 3),
+        (1, 4),
+        (0, 2),
+        (1, 3),
+        (0, 1),
+        (2, 4),
+        (1, 2),
+        (3, 4),
+        (2, 3),
+    ],
+    &[
+        (0, 5),
+        (1, 3),
+        (2, 4),
+        (1, 2),
+        (3, 4),
+        (0, 3),
+        (2, 5),
+        (0, 1),
+        (2, 3),
+        (4, 5),
+        (1, 2),
+        (3, 4),
+    ],
+    &[
+        (0, 6),
+        (2, 3),
+        (4, 5),
+        (0, 2),
+        (1, 4),
+        (3, 6),
+        (0, 1),
+        (2, 5),
+        (3, 4),
+        (1, 2),
+        (4, 6),
+        (2, 3),
+        (4, 5),
+        (1, 2),
+        (3, 4),
+        (5, 6),
+    ],
+    &[
+        (0, 2),
+        (1, 3),
+        (4, 6),
+        (5, 7),
+        (0, 4),
+        (1, 5),
+        (2, 6),
+        (3, 7),
+        (0, 1),
+        (2, 3),
+        (4, 5),
+        (6, 7),
+        (2, 4),
+        (3, 5),
+        (1, 4),
+        (3, 6),
+        (1, 2),
+        (3, 4),
+        (5, 6),
+    ],
+];
+
+/// Sorting of small arrays with sorting networks.
+pub trait SortNetExt {
+    /// Sort `self` with an optimal-size sorting network: a
+    /// fixed sequence of compare-exchanges of pairs of
+    /// elements, each done with [MutElemsExt::mut_swap_if].
+    /// Arrays of more than 8 elements are sorted with
+    /// `sort_unstable()` instead.
+    ///
+    /// Like `sort_unstable()`, this may reorder equal
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [3u8, 1, 4, 1, 5];
+    /// a.sort_net();
+    /// assert_eq!([1, 1, 3, 4, 5], a);
+    /// ```
+    fn sort_net(&mut self);
+}
+
+impl<const N: usize, T: Ord> SortNetExt for [T; N] {
+    fn sort_net(&mut self) {
+        let Some(network) = SORT_NETWORKS.get(N) else {
+            self.sort_unstable();
+            return;
+        };
+        for &(i, j) in network.iter() {
+            // The network's indices are distinct and less
+            // than `N`, so this cannot fail.
+            let _ = self.mut_swap_if(i, j, |a, b| a > b);
+        }
+    }
+}
+
+/// Mutable references to several elements of a `VecDeque`.
+#[cfg(feature = "alloc")]
+pub trait MutElemsDequeExt<T> {
+    /// Return mutable references to elements of `self` at
+    /// each of the logical index positions given by
+    /// `indices`: index 0 is the front of the deque.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of bounds,
+    /// or if any pair of indices is identical. The error is
+    /// for the first position in `indices`, scanning left to
+    /// right, that holds either an out-of-bounds index
+    /// ([MutElemsError::IndexBound]) or a repeat of an
+    /// earlier index ([MutElemsError::IndicesOverlap]).
+    fn mut_elems<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T> MutElemsDequeExt<T> for VecDeque<T> {
+    fn mut_elems<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        check_indices_in(indices, self.len())?;
+
+        let (front, back) = self.as_mut_slices();
+        let nfront = front.len();
+        let (front, back) = (front.as_mut_ptr(), back.as_mut_ptr());
+
+        // Safety: The ring buffer's contents are the two
+        // disjoint regions `front` and `back`, in logical
+        // order, so distinct in-bounds logical indices map to
+        // distinct in-bounds locations in exactly one of the
+        // regions. Indices have been checked for inequality
+        // and bounds.
+        Ok(core::array::from_fn(|i| unsafe {
+            let ix = *indices.get_unchecked(i);
+            if ix < nfront {
+                &mut *front.add(ix)
+            } else {
+                &mut *back.add(ix - nfront)
+            }
+        }))
+    }
+}
+
+/// Mutable references to several elements of a
+/// one-dimensional `ndarray` array or view, such as an
+/// `ArrayViewMut1`. The elements need not be contiguous.
+#[cfg(feature = "ndarray")]
+pub trait MutElemsViewExt<T> {
+    /// Return mutable references to elements of `self` at
+    /// each of the logical index positions given by
+    /// `indices`, following the stride of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    /// use ndarray::{s, Array1};
+    ///
+    /// let mut a = Array1::from(vec![1u8, 2, 3, 4, 5, 6]);
+    /// let mut evens = a.slice_mut(s![..;2]);
+    /// let [x, y] = evens.mut_elems(&[2, 0]).unwrap();
+    /// core::mem::swap(x, y);
+    /// assert_eq!(vec![5, 2, 3, 4, 1, 6], a.to_vec());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems], with bounds taken from the
+    /// logical length of `self`.
+    fn mut_elems<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+}
+
+#[cfg(feature = "ndarray")]
+impl<T, S> MutElemsViewExt<T> for ndarray::ArrayBase<S, ndarray::Ix1>
+where
+    S: ndarray::DataMut<Elem = T>,
+{
+    fn mut_elems<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        check_indices_in(indices, self.len())?;
+
+        let stride = self.strides()[0];
+        let base = self.as_mut_ptr();
+
+        // Safety: `base` points at logical element 0, and
+        // logical element `ix` is `ix * stride` elements from
+        // it, which is within the array's storage for every
+        // in-bounds `ix`. A mutable array has no repeated
+        // elements, so distinct logical indices are distinct
+        // locations. Indices have been checked for inequality
+        // and bounds.
+        Ok(core::array::from_fn(|i| unsafe {
+            let ix = *indices.get_unchecked(i);
+            &mut *base.offset(ix as isize * stride)
+        }))
+    }
+}
+
+/// Shared references to several elements of a slice of
+/// `Cell`s, through which the elements can be mutated.
+///
+/// The uniqueness requirement of [MutElemsExt] is about
+/// `&mut`: a `Cell` can be mutated through aliased shared
+/// references, so here indices may repeat.
+#[cfg(feature = "alloc")]
+pub trait CellElemsExt<T> {
+    /// Return a `Vec` of references to the cells of `self` at
+    /// each of the index positions given by `indices`, which
+    /// may repeat.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::cell::Cell;
+    /// use mut_elems::*;
+    ///
+    /// let a = [Cell::new(1u8), Cell::new(2)];
+    /// let es = a.elems_allowing_dup(&[1, 0, 1]).unwrap();
+    /// es[0].set(es[1].get() + es[2].get());
+    /// assert_eq!(3, a[1].get());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return [MutElemsError::IndexBound] if any of the
+    /// indices are out of bounds.
+    fn elems_allowing_dup(&self, indices: &[usize]) -> Result<Vec<&Cell<T>>, MutElemsError>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T> CellElemsExt<T> for [Cell<T>] {
+    fn elems_allowing_dup(&self, indices: &[usize]) -> Result<Vec<&Cell<T>>, MutElemsError> {
+        check_bounds(indices, self.len())?;
+        Ok(indices.iter().map(|&ix| &self[ix]).collect())
+    }
+}
+
+/// Pinned mutable references to several elements of a
+/// pinned slice.
+///
+/// The elements of a pinned slice are pinned themselves, so
+/// each reference handed out is pinned in turn.
+pub trait MutElemsPinExt<T> {
+    /// Return pinned mutable references to elements of
+    /// `self` at each of the index positions given by
+    /// `indices`, as with [MutElemsExt::mut_elems].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::pin::Pin;
+    /// use mut_elems::*;
+    ///
+    /// let mut a = [1u8, 2, 3];
+    /// let mut pinned = Pin::new(&mut a[..]);
+    /// let [x, mut y] = pinned.mut_elems_pinned(&[0, 2]).unwrap();
+    /// y.set(*x + 10);
+    /// assert_eq!([1, 2, 11], a);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of
+    /// bounds, or if any pair of indices is identical.
+    fn mut_elems_pinned<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[Pin<&'a mut T>; N], MutElemsError>;
+}
+
+impl<T> MutElemsPinExt<T> for Pin<&mut [T]> {
+    fn mut_elems_pinned<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[Pin<&'a mut T>; N], MutElemsError> {
+        // Safety: Nothing is moved out of the slice, and each
+        // element reference is immediately pinned again.
+        let slice = unsafe { self.as_mut().get_unchecked_mut() };
+        let es = slice.mut_elems(indices)?;
+        // Safety: The elements of a pinned slice are pinned.
+        Ok(es.map(|e| unsafe { Pin::new_unchecked(e) }))
+    }
+}
+
+/// Mutable references to several elements of a slice behind
+/// a guard, such as the `MutexGuard` of a lock.
+///
+/// The references returned borrow the guard mutably, so the
+/// guard, and whatever lock it holds, must outlive them.
+pub trait MutElemsGuardExt<T> {
+    /// Return mutable references to elements of the slice
+    /// behind `self` at each of the index positions given by
+    /// `indices`, as with [MutElemsExt::mut_elems].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Mutex;
+    /// use mut_elems::*;
+    ///
+    /// let lock = Mutex::new(vec![1u8, 2, 3]);
+    /// let mut guard = lock.lock().unwrap();
+    /// let [a, b] = guard.mut_elems_guard(&[2, 0]).unwrap();
+    /// core::mem::swap(a, b);
+    /// drop(guard);
+    /// assert_eq!(vec![3, 2, 1], *lock.lock().unwrap());
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use std::sync::Mutex;
+    /// use mut_elems::*;
+    ///
+    /// let lock = Mutex::new(vec![1u8, 2, 3]);
+    /// let mut guard = lock.lock().unwrap();
+    /// let [a] = guard.mut_elems_guard(&[0]).unwrap();
+    /// drop(guard);
+    /// *a = 0;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of
+    /// bounds, or if any pair of indices is identical.
+    fn mut_elems_guard<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError>;
+}
+
+impl<T, G> MutElemsGuardExt<T> for G
+where
+    G: DerefMut,
+    G::Target: AsMut<[T]>,
+{
+    fn mut_elems_guard<'a, const N: usize>(
+        &'a mut self,
+        indices: &[usize; N],
+    ) -> Result<[&'a mut T; N], MutElemsError> {
+        self.deref_mut().as_mut().mut_elems(indices)
+    }
+}
+
+/// Mutable references to several bytes of a string.
+pub trait MutBytesExt {
+    /// Return mutable references to the bytes of `self` at
+    /// each of the byte index positions given by `indices`,
+    /// each of which must start a `char`.
+    ///
+    /// # Safety
+    ///
+    /// As with `str::as_bytes_mut()`, the caller must ensure
+    /// that the string is valid UTF-8 once the references are
+    /// dropped. Starting at a `char` boundary, overwriting an
+    /// ASCII byte with an ASCII byte always does so.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mut_elems::*;
+    ///
+    /// let mut s = String::from("héllo wörld");
+    /// let space = s.find(' ').unwrap();
+    /// // Safety: Only ASCII bytes are written, over ASCII bytes.
+    /// let [h, w] = unsafe { s.mut_bytes_at(&[0, space + 1]) }.unwrap();
+    /// h.make_ascii_uppercase();
+    /// w.make_ascii_uppercase();
+    /// assert_eq!("Héllo Wörld", s);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of
+    /// bounds or if any pair of indices is identical, as with
+    /// [MutElemsExt::mut_elems]. Otherwise will return
+    /// [MutElemsError::NotCharBoundary] for the first index
+    /// that is inside a `char`.
+    unsafe fn mut_bytes_at<const N: usize>(
+        &mut self,
+        indices: &[usize; N],
+    ) -> Result<[&mut u8; N], MutElemsError>;
+}
+
+impl MutBytesExt for str {
+    unsafe fn mut_bytes_at<const N: usize>(
+        &mut self,
+        indices: &[usize; N],
+    ) -> Result<[&mut u8; N], MutElemsError> {
+        check_indices_in(indices, self.len())?;
+        for (position, &index) in indices.iter().enumerate() {
+            if !self.is_char_boundary(index) {
+                return Err(NotCharBoundary { position, index });
+            }
+        }
+
+        // Safety: The caller guarantees that the string is left
+        // valid UTF-8.
+        let bytes = unsafe { self.as_bytes_mut() };
+        // Safety: Indices have been checked for inequality and
+        // bounds.
+        Ok(unsafe { bytes.mut_elems_unchecked(indices) })
+    }
+}
+
+/// Mutable references to several values of a `HashMap`.
+#[cfg(feature = "std")]
+pub trait MutValuesExt<K, V> {
+    /// Return mutable references to the values of `self`
+    /// for each of the keys given by `keys`.
+    ///
+    /// All keys must be unique, as Rust does not allow
+    /// multiple mutable references to the same object.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the keys are not in
+    /// the map, or if any pair of keys is equal.
+    fn mut_values<'a, const N: usize>(
+        &'a mut self,
+        keys: &[K; N],
+    ) -> Result<[&'a mut V; N], MutElemsError>;
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> MutValuesExt<K, V> for HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn mut_values<'a, const N: usize>(
+        &'a mut self,
+        keys: &[K; N],
+    ) -> Result<[&'a mut V; N], MutElemsError> {
+        // Keys are only `Eq`, so compare pairwise. The scan
+        // order reports the same pair as `mut_elems()` would.
+        for second in 1..N {
+            for first in 0..second {
+                if keys[first] == keys[second] {
+                    return Err(KeysOverlap { first, second });
+                }
+            }
+        }
+
+        let values = self.get_disjoint_mut(core::array::from_fn(|i| &keys[i]));
+        if let Some(position) = values.iter().position(Option::is_none) {
+            return Err(MissingKey { position });
+        }
+        Ok(values.map(|v| v.unwrap()))
+    }
+}
+
+/// Parallel iteration over several elements of a slice.
+#[cfg(feature = "rayon")]
+pub trait ParMutElemsExt<T: Send> {
+    /// Return a parallel iterator over mutable references to
+    /// elements of `self` at each of the index positions
+    /// given by `indices`, in the order given.
+    ///
+    /// ```
+    /// use mut_elems::*;
+    /// use rayon::prelude::*;
+    ///
+    /// let mut v = vec![1u64, 2, 3, 4];
+    /// v.par_mut_elems(&[3, 0]).unwrap().for_each(|e| *e *= 10);
+    /// assert_eq!(vec![10, 2, 3, 40], v);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the indices are out of bounds,
+    /// or if any pair of indices is identical.
+    fn par_mut_elems<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<rayon::vec::IntoIter<&'a mut T>, MutElemsError>;
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Send> ParMutElemsExt<T> for [T] {
+    fn par_mut_elems<'a>(
+        &'a mut self,
+        indices: &[usize],
+    ) -> Result<rayon::vec::IntoIter<&'a mut T>, MutElemsError> {
+        use rayon::iter::IntoParallelIterator;
+
+        // `&mut T` is `Send` when `T` is, so the disjoint
+        // references can be handed out to worker threads as is.
+        Ok(self.mut_elems_slice(indices)?.into_par_iter())
+    }
+}
+
+/// A tuple of `usize` indices, for [MutElemsExt::mut_indexed].
+pub trait MutIndexTuple<'a, T: 'a> {
+    /// Tuple of mutable references, one per index.
+    type Output;
+
+    /// Return a tuple of mutable references to elements of
+    /// `target` at each of the index positions in `self`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems].
+    fn mut_elems_of(self, target: &'a mut [T]) -> Result<Self::Output, MutElemsError>;
+}
+
+/// Generate [MutIndexTuple] impls for each listed arity.
+macro_rules! mut_index_tuple_impls {
+    ($(($($ix:ident),+);)*) => {
+        $(
+            impl<'a, T: 'a> MutIndexTuple<'a, T> for ($(mut_index_tuple_impls!(@usize $ix),)+) {
+                type Output = ($(mut_index_tuple_impls!(@ref $ix),)+);
+
+                fn mut_elems_of(self, target: &'a mut [T]) -> Result<Self::Output, MutElemsError> {
+                    let ($($ix,)+) = self;
+                    let [$($ix),+] = target.mut_elems(&[$($ix),+])?;
+                    Ok(($($ix,)+))
+                }
+            }
+        )*
+    };
+    (@usize $ix:ident) => { usize };
+    (@ref $ix:ident) => { &'a mut T };
+}
+
+mut_index_tuple_impls! {
+    (i0);
+    (i0, i1);
+    (i0, i1, i2);
+    (i0, i1, i2, i3);
+    (i0, i1, i2, i3, i4);
+    (i0, i1, i2, i3, i4, i5);
+    (i0, i1, i2, i3, i4, i5, i6);
+    (i0, i1, i2, i3, i4, i5, i6, i7);
+    (i0, i1, i2, i3, i4, i5, i6, i7, i8);
+    (i0, i1, i2, i3, i4, i5, i6, i7, i8, i9);
+    (i0, i1, i2, i3, i4, i5, i6, i7, i8, i9, i10);
+    (i0, i1, i2, i3, i4, i5, i6, i7, i8, i9, i10, i11);
+}
+
+/// A tuple of mutable slices laid out as a struct of arrays,
+/// indexed in parallel: the elements at the same index in
+/// each slice belong together.
+///
+/// # Examples
+///
+/// ```
+/// use mut_elems::*;
+///
+/// let mut xs = vec![0.0f32, 1.0, 2.0];
+/// let mut names = vec!["a", "b", "c"];
+/// let ([x0, x2], [n0, n2]) = (&mut xs[..], &mut names[..]).mut_elems_soa(&[0, 2]).unwrap();
+/// core::mem::swap(x0, x2);
+/// core::mem::swap(n0, n2);
+/// assert_eq!(vec![2.0, 1.0, 0.0], xs);
+/// assert_eq!(vec!["c", "b", "a"], names);
+/// ```
+pub trait MutElemsSoa<'a, const N: usize> {
+    /// Tuple of arrays of mutable references, one array per
+    /// slice.
+    type Output;
+
+    /// Return, for each slice in `self`, mutable references
+    /// to its elements at each of the index positions given
+    /// by `indices`. The indices are checked once for all
+    /// the slices.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error under the same conditions as
+    /// [MutElemsExt::mut_elems] on the shortest slice, whose
+    /// length is the one reported in
+    /// [MutElemsError::IndexBound].
+    fn mut_elems_soa(self, indices: &[usize; N]) -> Result<Self::Output, MutElemsError>;
+}
+
+/// Generate [MutElemsSoa] impls for each listed arity.
+macro_rules! mut_elems_soa_impls {
+    ($(($($s:ident: $t:ident),+);)*) => {
+        $(
+            impl<'a, $($t,)+ const N: usize> MutElemsSoa<'a, N> for ($(&'a mut [$t],)+) {
+                type Output = ($([&'a mut $t; N],)+);
+
+                fn mut_elems_soa(self, indices: &[usize; N]) -> Result<Self::Output, MutElemsError> {
+                    let ($($s,)+) = self;
+                    let length = usize::MAX$(.min($s.len()))+;
+                    check_indices_in(indices, length)?;
+
+                    // Safety: Indices have been checked for
+                    // inequality, and for bounds against the
+                    // shortest slice.
+                    Ok(($(unsafe { $s.mut_elems_unchecked(indices) },)+))
+                }
+            }
+        )*
+    };
+}
+
+mut_elems_soa_impls! {
+    (s0: T0, s1: T1);
+    (s0: T0, s1: T1, s2: T2);
+    (s0: T0, s1: T1, s2: T2, s3: T3);
+    (s0: T0, s1: T1, s2: T2, s3: T3, s4: T4);
+    (s0: T0, s1: T1, s2: T2, s3: T3, s4: T4, s5: T5);
+}
+
+/// Generate [MutTupleExt] with one method per listed arity.
+macro_rules! mut_tuple_ext {
+    ($($name:ident: $n:literal ($($ix:ident),+);)*) => {
+        /// Mutable references to elements of a slice, returned
+        /// as a tuple rather than an array, for small fixed
+        /// numbers of elements.
+        pub trait MutTupleExt<T> {
+            $(
+                #[doc = concat!(
+                    "Return a tuple of mutable references to the ",
+                    $n,
+                    " elements of `self` at the given index positions.\n",
+                    "\n",
+                    "# Errors\n",
+                    "\n",
+                    "Will return an error under the same conditions as ",
+                    "[MutElemsExt::mut_elems].",
+                )]
+                #[allow(clippy::too_many_arguments)]
+                fn $name(
+                    &mut self,
+                    $($ix: usize),+
+                ) -> Result<($(mut_tuple_ext!(@ref $ix)),+), MutElemsError>;
+            )*
+        }
+
+        impl<T> MutTupleExt<T> for [T] {
+            $(
+                #[allow(clippy::too_many_arguments)]
+                fn $name(
+                    &mut self,
+                    $($ix: usize),+
+                ) -> Result<($(mut_tuple_ext!(@ref $ix)),+), MutElemsError> {
+                    let [$($ix),+] = self.mut_elems(&[$($ix),+])?;
+                    Ok(($($ix),+))
+                }
+            )*
+        }
+    };
+    (@ref $ix:ident) => { &mut T };
+}
+
+mut_tuple_ext! {
+    mut_pair: 2 (i, j);
+    mut_triple: 3 (i, j, k);
+    mut_tuple2: 2 (i0, i1);
+    mut_tuple3: 3 (i0, i1, i2);
+    mut_tuple4: 4 (i0, i1, i2, i3);
+    mut_tuple5: 5 (i0, i1, i2, i3, i4);
+    mut_tuple6: 6 (i0, i1, i2, i3, i4, i5);
+    mut_tuple7: 7 (i0, i1, i2, i3, i4, i5, i6);
+    mut_tuple8: 8 (i0, i1, i2, i3, i4, i5, i6, i7);
+}
+
+/// Return mutable references to elements of a slice, array
+/// or `Vec` at each of the given index positions, as with
+/// [MutElemsExt::mut_elems].
+///
+/// When every index is an integer literal, the indices are
+/// checked for distinctness at compile time and the runtime
+/// overlap check is skipped. Bounds are always checked at
+/// runtime.
+///
+/// ```
+/// use mut_elems::*;
+///
+/// let mut v = vec![1u8, 2, 3, 4];
+/// let [a, b] = mut_elems!(v, 3, 1).unwrap();
+/// core::mem::swap(a, b);
+/// assert_eq!(vec![1, 4, 3, 2], v);
+///
+/// let i = 2;
+/// let [a, b] = mut_elems!(v, i, 0).unwrap();
+/// *a += *b;
+/// assert_eq!(vec![1, 4, 4, 2], v);
+///
+/// assert!(mut_elems!(v, 0, 4).is_err());
+/// ```
+///
+/// Repeated literal indices are rejected:
+///
+/// ```compile_fail
+/// use mut_elems::*;
+///
+/// let mut v = vec![1u8, 2, 3, 4];
+/// let _ = mut_elems!(v, 1, 1);
+/// ```
+#[macro_export]
+macro_rules! mut_elems {
+    ($s:expr $(, $ix:literal)+ $(,)?) => {{
+        const _: () = ::core::assert!(
+            $crate::__distinct(&[$($ix),+]),
+            "repeated index in mut_elems!",
+        );
+        let s: &mut [_] = &mut $s[..];
+        // Safety: The indices were checked for distinctness
+        // at compile time.
+        unsafe { $crate::__mut_elems_distinct(s, &[$($ix),+]) }
+    }};
+    ($s:expr $(, $ix:expr)+ $(,)?) => {{
+        let s: &mut [_] = &mut $s[..];
+        $crate::MutElemsExt::mut_elems(s, &[$($ix),+])
+    }};
+}
+
+/// Compile-time distinctness check for [mut_elems!].
+#[doc(hidden)]
+pub const fn __distinct(indices: &[usize]) -> bool {
+    let mut i = 0;
+    while i < indices.len() {
+        let mut j = i + 1;
+        while j < indices.len() {
+            if indices[i] == indices[j] {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Bounds-checked access for [mut_elems!].
+///
+/// # Safety
+///
+/// The indices must be pairwise distinct.
+#[doc(hidden)]
+pub unsafe fn __mut_elems_distinct<'a, T, const N: usize>(
+    s: &'a mut [T],
+    indices: &[usize; N],
+) -> Result<[&'a mut T; N], MutElemsError> {
+    check_bounds(indices, s.len())?;
+    // Safety: The caller guarantees distinctness, and bounds
+    // have been checked.
+    Ok(unsafe { s.mut_elems_unchecked(indices) })
+}
+
+#[test]
+fn test_mut_elems() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    assert_eq!([&1], test_array.mut_elems(&[0]).unwrap());
+    assert_eq!([&4], test_array.mut_elems(&[3]).unwrap());
+    assert_eq!([&2, &3], test_array.mut_elems(&[1, 2]).unwrap());
+    assert_eq!([&1, &3, &4], test_array.mut_elems(&[0, 2, 3]).unwrap());
+
+    match test_array.mut_elems(&[4]) {
+        Err(MutElemsError::IndexBound {
+            position,
+            index,
+            length,
+        }) => {
+            assert_eq!(position, 0);
+            assert_eq!(index, 4);
+            assert_eq!(length, 4);
+        }
+        _ => panic!(),
+    }
+
+    match test_array.mut_elems(&[1, 2, 1]) {
+        Err(MutElemsError::IndicesOverlap {
+            first,
+            second,
+            index,
+        }) => {
+            assert_eq!(first, 0);
+            assert_eq!(second, 2);
+            assert_eq!(index, 1);
+        }
+        _ => panic!(),
+    }
+
+    let es = test_array.mut_elems(&[1, 3]).unwrap();
+    *es[0] = 5;
+    *es[1] = 7;
+    assert_eq!([1, 5, 3, 7], test_array);
+}
+
+#[test]
+fn test_as_mut_elems() {
+    let mut test_array = [1u8, 2, 3, 4];
+    let es = test_array.as_mut_elems();
+    assert_eq!([&1, &2, &3, &4], es);
+    *es[1] = 5;
+    *es[3] = 7;
+    assert_eq!([1, 5, 3, 7], test_array);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_as_mut_elems_vec() {
+    use alloc::vec;
+
+    let mut test_vec = vec![1u8, 2, 3, 4];
+    let mut es = test_vec.as_mut_elems_vec();
+    assert_eq!(vec![&1, &2, &3, &4], es);
+    *es[1] = 5;
+    *es[3] = 7;
+    assert_eq!(vec![1, 5, 3, 7], test_vec);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_as_mut_elems_vec_receivers() {
+    use alloc::vec;
+
+    let mut test_array = [1u8, 2, 3];
+    let es = test_array.as_mut_elems_vec();
+    assert_eq!(vec![&1, &2, &3], es);
+
+    let mut test_vec = vec![1u8, 5, 3, 7];
+    let test_slice: &mut [u8] = &mut test_vec[1..3];
+    let mut es = test_slice.as_mut_elems_vec();
+    assert_eq!(vec![&5, &3], es);
+    *es[1] = 6;
+    let es = <[u8] as AsMutElemsVecExt<u8>>::as_mut_elems_vec(test_slice);
+    assert_eq!(vec![&5, &6], es);
+    assert_eq!(vec![1, 5, 6, 7], test_vec);
+}
+
+#[test]
+fn test_mut_elems_pair_overlap() {
+    let mut test_array = [1u8, 2, 3, 4];
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 2,
+        }),
+        test_array.mut_elems(&[2, 2]),
+    );
+}
+
+#[test]
+fn test_mut_elems_overlap_order() {
+    let mut test_array = [1u8, 2, 3, 4, 5, 6];
+
+    // The reported overlap is the first repeat found scanning
+    // left to right, not the smallest repeated index.
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 1,
+            second: 2,
+            index: 3,
+        }),
+        test_array.mut_elems(&[5, 3, 3, 5]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 5,
+        }),
+        test_array.mut_elems(&[5, 3, 5, 3]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 3,
+            index: 2,
+        }),
+        test_array.mut_elems(&[2, 0, 1, 2, 2]),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_slice() {
+    use alloc::vec;
+
+    let mut test_vec = vec![1u8, 2, 3, 4];
+
+    assert!(test_vec.mut_elems_slice(&[]).unwrap().is_empty());
+    assert_eq!(vec![&2, &3], test_vec.mut_elems_slice(&[1, 2]).unwrap());
+    assert_eq!(
+        vec![&4, &1, &3],
+        test_vec.mut_elems_slice(&[3, 0, 2]).unwrap(),
+    );
+
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 4,
+            length: 4,
+        }),
+        test_vec.mut_elems_slice(&[0, 4]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 3,
+        }),
+        test_vec.mut_elems_slice(&[3, 3]),
+    );
+    assert_eq!(
+        test_vec.mut_elems(&[1, 2, 1]).map(|_| ()),
+        test_vec.mut_elems_slice(&[1, 2, 1]).map(|_| ()),
+    );
+
+    let mut es = test_vec.mut_elems_slice(&[1, 3]).unwrap();
+    *es[0] = 5;
+    *es[1] = 7;
+    assert_eq!(vec![1, 5, 3, 7], test_vec);
+}
+
+#[test]
+fn test_mut_elems_unchecked() {
+    let mut test_array = [1u8, 2, 3, 4];
+    // Safety: indices are in-bounds and distinct.
+    let es = unsafe { test_array.mut_elems_unchecked(&[3, 0, 2]) };
+    assert_eq!([&4, &1, &3], es);
+    *es[0] = 7;
+    *es[1] = 5;
+    assert_eq!([5, 2, 3, 7], test_array);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_report_all() {
+    use alloc::vec;
+
+    let mut test_array = [1u8, 2, 3, 4];
+
+    assert_eq!(
+        [&2, &4, &1],
+        test_array.mut_elems_report_all(&[1, 3, 0]).unwrap(),
+    );
+
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlapAll {
+            groups: vec![(3, vec![0, 2, 5]), (1, vec![1, 4])],
+        }),
+        test_array.mut_elems_report_all(&[3, 1, 3, 0, 1, 3]),
+    );
+
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 7,
+            length: 4,
+        }),
+        test_array.mut_elems_report_all(&[0, 7, 2]),
+    );
+}
+
+#[test]
+fn test_get_disjoint_mut() {
+    let mut test_array = [1u8, 2, 3, 4];
+    let test_slice: &mut [u8] = &mut test_array;
+
+    let es = MutElemsExt::get_disjoint_mut(test_slice, [3, 1]).unwrap();
+    assert_eq!([&4, &2], es);
+    *es[0] = 7;
+    *es[1] = 5;
+    assert_eq!([1, 5, 3, 7], test_slice);
+
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 2,
+        }),
+        MutElemsExt::get_disjoint_mut(test_slice, [2, 2]),
+    );
+}
+
+#[test]
+fn test_mut_tuple() {
+    let mut test_array = [1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+    let (a, b) = test_array.mut_tuple2(3, 1).unwrap();
+    assert_eq!((&mut 4, &mut 2), (&mut *a, &mut *b));
+    core::mem::swap(a, b);
+    assert_eq!([1, 4, 3, 2], test_array[..4]);
+
+    let (a, b, c) = test_array.mut_tuple3(0, 5, 9).unwrap();
+    *a += *b + *c;
+    assert_eq!(17, test_array[0]);
+
+    let t = test_array.mut_tuple8(0, 1, 2, 3, 4, 5, 6, 7).unwrap();
+    assert_eq!(&mut 8, t.7);
+
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 5,
+        }),
+        test_array.mut_tuple3(5, 6, 5),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 10,
+            length: 10,
+        }),
+        test_array.mut_tuple2(0, 10),
+    );
+}
+
+#[test]
+fn test_mut_elems_macro() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    assert_eq!([&4, &2], mut_elems!(test_array, 3, 1).unwrap());
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 2,
+            index: 4,
+            length: 4,
+        }),
+        mut_elems!(test_array, 0, 1, 4),
+    );
+
+    let (i, j) = (2, 2);
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 2,
+        }),
+        mut_elems!(test_array, i, j),
+    );
+
+    let test_slice: &mut [u8] = &mut test_array;
+    let [a, b, c] = mut_elems!(test_slice, 0, 2, 3).unwrap();
+    *a = 5;
+    *b = 6;
+    *c = 7;
+    assert_eq!([5, 2, 6, 7], test_array);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_rows() {
+    use alloc::vec;
+
+    let mut matrix = vec![vec![1.0f64, 2.0], vec![3.0, 4.0]];
+    matrix.push(vec![5.0, 6.0]);
+
+    // Row operation: row 2 -= 5 * row 0.
+    let [r0, r2] = matrix.mut_rows(&[0, 2]).unwrap();
+    for (x, y) in r2.iter_mut().zip(r0.iter()) {
+        *x -= 5.0 * y;
+    }
+    assert_eq!(vec![0.0, -4.0], matrix[2]);
+
+    let [r1, r0] = matrix.mut_rows(&[1, 0]).unwrap();
+    core::mem::swap(r1, r0);
+    assert_eq!(vec![3.0, 4.0], matrix[0]);
+
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 3,
+            length: 3,
+        }),
+        matrix.mut_rows(&[3]),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_deque() {
+    // Advance the head of the ring buffer near its end, so
+    // that the contents are split.
+    let mut test_deque: VecDeque<u8> = VecDeque::with_capacity(5);
+    let cap = test_deque.capacity();
+    for _ in 0..cap - 2 {
+        test_deque.push_back(0);
+        test_deque.pop_front();
+    }
+    test_deque.extend([1, 2, 3, 4, 5]);
+    assert_eq!(cap, test_deque.capacity());
+    assert!(!test_deque.as_slices().1.is_empty());
+
+    let es = MutElemsDequeExt::mut_elems(&mut test_deque, &[4, 0, 2]).unwrap();
+    assert_eq!([&5, &1, &3], es);
+    *es[0] = 7;
+    *es[1] = 6;
+    assert_eq!(VecDeque::from([6, 2, 3, 4, 7]), test_deque);
+
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 5,
+            length: 5,
+        }),
+        test_deque.mut_elems(&[0, 5]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 3,
+        }),
+        test_deque.mut_elems(&[3, 1, 3]),
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_mut_values() {
+    let mut test_map: HashMap<&str, u8> = [("a", 1), ("b", 2), ("c", 3)].into();
+
+    let [c, a] = test_map.mut_values(&["c", "a"]).unwrap();
+    core::mem::swap(c, a);
+    assert_eq!(3, test_map["a"]);
+    assert_eq!(1, test_map["c"]);
+
+    assert_eq!(
+        Err(MutElemsError::KeysOverlap {
+            first: 0,
+            second: 2,
+        }),
+        test_map.mut_values(&["b", "a", "b"]),
+    );
+    assert_eq!(
+        Err(MutElemsError::MissingKey { position: 1 }),
+        test_map.mut_values(&["b", "d"]),
+    );
+}
+
+#[test]
+fn test_mut_ranges() {
+    let mut test_array = [1u8, 2, 3, 4, 5, 6, 7, 8];
+
+    let [a, b, c] = test_array.mut_ranges(&[5..8, 0..2, 2..2]).unwrap();
+    assert_eq!([6, 7, 8], a);
+    assert_eq!([1, 2], b);
+    assert!(c.is_empty());
+    a[..2].swap_with_slice(b);
+    assert_eq!([6, 7, 3, 4, 5, 1, 2, 8], test_array);
+
+    assert_eq!(
+        Err(MutElemsError::RangesOverlap {
+            first: 0,
+            second: 2,
+        }),
+        test_array.mut_ranges(&[3..5, 0..1, 4..6]),
+    );
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed = 3..2;
+    assert_eq!(
+        Err(MutElemsError::RangeBound {
+            position: 1,
+            start: 3,
+            end: 2,
+            length: 8,
+        }),
+        test_array.mut_ranges(&[0..1, reversed]),
+    );
+    assert_eq!(
+        Err(MutElemsError::RangeBound {
+            position: 1,
+            start: 6,
+            end: 9,
+            length: 8,
+        }),
+        test_array.mut_ranges(&[0..0, 6..9]),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_iter() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let es: Vec<&mut u8> = test_array
+        .mut_elems_iter([3, 1])
+        .collect::<Result<_, _>>()
+        .unwrap();
+    for e in es {
+        *e *= 10;
+    }
+    assert_eq!([1, 20, 3, 40], test_array);
+
+    let mut it = test_array.mut_elems_iter((0..6).map(|i| i % 5));
+    let e0 = it.next().unwrap().unwrap();
+    let e1 = it.next().unwrap().unwrap();
+    *e0 = 5;
+    *e1 = 6;
+    assert!(it.next().unwrap().is_ok());
+    assert!(it.next().unwrap().is_ok());
+    assert_eq!(
+        Some(Err(MutElemsError::IndexBound {
+            position: 4,
+            index: 4,
+            length: 4,
+        })),
+        it.next(),
+    );
+    assert_eq!(
+        Some(Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 5,
+            index: 0,
+        })),
+        it.next(),
+    );
+    assert_eq!(None, it.next());
+    assert_eq!([5, 6, 3, 40], test_array);
+}
+
+#[test]
+fn test_mut_elems_sorted() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    assert_eq!(
+        [&1, &3, &4],
+        test_array.mut_elems_sorted(&[0, 2, 3]).unwrap()
+    );
+    assert_eq!([&2], test_array.mut_elems_sorted(&[1]).unwrap());
+    assert_eq!(0, test_array.mut_elems_sorted(&[]).unwrap().len());
+
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 1,
+            second: 2,
+            index: 2,
+        }),
+        test_array.mut_elems_sorted(&[0, 2, 2]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesUnsorted { position: 2 }),
+        test_array.mut_elems_sorted(&[0, 2, 1]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 2,
+            index: 4,
+            length: 4,
+        }),
+        test_array.mut_elems_sorted(&[0, 1, 4]),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_check_indices_slice_in() {
+    // The linear, bitset, stack and heap paths all report
+    // original positions, first before second.
+    for n in [
+        LINEAR_SCAN_LIMIT,
+        STACK_PAIRS,
+        STACK_PAIRS + 1,
+        4 * STACK_PAIRS,
+    ] {
+        for length in [n, BITSET_LIMIT + 1] {
+            let mut indices: Vec<usize> = (0..n).rev().collect();
+            indices[n - 1] = indices[1];
+            assert_eq!(
+                Err(MutElemsError::IndicesOverlap {
+                    first: 1,
+                    second: n - 1,
+                    index: n - 2,
+                }),
+                check_indices_slice_in(&indices, length),
+            );
+            indices[n - 1] = n;
+            let expected = if length > n {
+                Ok(())
+            } else {
+                Err(MutElemsError::IndexBound {
+                    position: n - 1,
+                    index: n,
+                    length,
+                })
+            };
+            assert_eq!(expected, check_indices_slice_in(&indices, length));
+        }
+    }
+}
+
+#[test]
+fn test_check_indices_bitset() {
+    let indices = [5, 3, 7, 3, 5];
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 1,
+            second: 3,
+            index: 3,
+        }),
+        check_indices_bitset(&indices, 8, 0),
+    );
+    assert_eq!(
+        check_indices_in(&indices, BITSET_LIMIT + 1),
+        check_indices_in(&indices, 8)
+    );
+    assert_eq!(Ok(()), check_indices_bitset(&[0, 64, 63, 65], 66, 0));
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 8,
+            length: 8,
+        }),
+        check_indices_bitset(&[1, 8, 1], 8, 0),
+    );
+}
+
+#[test]
+fn test_check_indices_precedence() {
+    // The earliest bad position wins, whether it is out of
+    // bounds or a repeat, on every strategy.
+    let overlap_first = [1, 2, 1, 9, 9, 0, 3, 4, 5, 6];
+    let bound_first = [1, 2, 9, 1, 9, 0, 3, 4, 5, 6];
+    for length in [9, BITSET_LIMIT + 1] {
+        let overlap = Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 1,
+        });
+        assert_eq!(overlap, check_indices_in(&overlap_first, length));
+        assert_eq!(overlap, check_indices_linear(&overlap_first, length));
+    }
+    let bound = Err(MutElemsError::IndexBound {
+        position: 2,
+        index: 9,
+        length: 9,
+    });
+    assert_eq!(bound, check_indices_in(&bound_first, 9));
+    assert_eq!(bound, check_indices_linear(&bound_first, 9));
+    let mut buf = [(0, 0); 10];
+    assert_eq!(bound, check_indices_sort(&bound_first, 9, &mut buf));
+
+    let mut test_array = [1u8, 2, 3, 4];
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 4,
+            length: 4,
+        }),
+        test_array.mut_elems(&[4, 0, 0]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 0,
+        }),
+        test_array.mut_elems(&[0, 0, 4]),
+    );
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_mut_elems() {
+    use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+
+    let mut test_vec: Vec<u64> = (0..100).collect();
+    let indices: Vec<usize> = (0..100).step_by(7).collect();
+
+    test_vec
+        .par_mut_elems(&indices)
+        .unwrap()
+        .enumerate()
+        .for_each(|(i, e)| *e += 1000 * i as u64);
+    for (i, &ix) in indices.iter().enumerate() {
+        assert_eq!(ix as u64 + 1000 * i as u64, test_vec[ix]);
+    }
+    assert_eq!(1, test_vec[1]);
+
+    assert!(matches!(
+        test_vec.par_mut_elems(&[1, 2, 1]),
+        Err(MutElemsError::IndicesOverlap { .. }),
+    ));
+}
+
+#[test]
+fn test_mut_elems_generic() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    assert_eq!([&4, &1], test_array.mut_elems_generic(&[3u32, 0]).unwrap());
+    assert_eq!([&2, &3], test_array.mut_elems_generic(&[1u8, 2]).unwrap());
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 1,
+        }),
+        test_array.mut_elems_generic(&[1u16, 2, 1]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 4,
+            length: 4,
+        }),
+        test_array.mut_elems_generic(&[4u64]),
+    );
+
+    #[derive(Clone, Copy)]
+    struct Signed(i32);
+    impl ElemIndex for Signed {
+        fn to_usize(self) -> Option<usize> {
+            usize::try_from(self.0).ok()
+        }
+    }
+    assert_eq!(
+        Err(MutElemsError::IndexConversion { position: 1 }),
+        test_array.mut_elems_generic(&[Signed(0), Signed(-1)]),
+    );
+}
+
+#[test]
+fn test_mut_elems_signed() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    assert_eq!(
+        [&4, &1, &3],
+        test_array.mut_elems_signed(&[-1, 0, 2]).unwrap()
+    );
+    assert_eq!([&1], test_array.mut_elems_signed(&[-4]).unwrap());
+
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 0,
+        }),
+        test_array.mut_elems_signed(&[0, -4]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 2,
+        }),
+        test_array.mut_elems_signed(&[-2, 3, 2]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: -5isize as usize,
+            length: 4,
+        }),
+        test_array.mut_elems_signed(&[0, -5]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 4,
+            length: 4,
+        }),
+        test_array.mut_elems_signed(&[4]),
+    );
+}
+
+#[test]
+fn test_mut_elems_opt() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    if let Some([a, b]) = test_array.mut_elems_opt(&[0, 3]) {
+        core::mem::swap(a, b);
+    }
+    assert_eq!([4, 2, 3, 1], test_array);
+
+    assert_eq!(None, test_array.mut_elems_opt(&[1, 1]));
+    assert_eq!(None, test_array.mut_elems_opt(&[0, 4, 2]));
+}
+
+#[test]
+fn test_mut_elems_with() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let sum = test_array
+        .mut_elems_with(&[1, 3], |[a, b]| {
+            *a += 10;
+            *b += 10;
+            *a + *b
+        })
+        .unwrap();
+    assert_eq!(26, sum);
+    assert_eq!([1, 12, 3, 14], test_array);
+
+    let mut called = false;
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 4,
+            length: 4,
+        }),
+        test_array.mut_elems_with(&[4], |_| called = true),
+    );
+    assert!(!called);
+}
+
+#[test]
+fn test_mut_pair_triple() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let (a, b) = test_array.mut_pair(0, 3).unwrap();
+    core::mem::swap(a, b);
+    assert_eq!([4, 2, 3, 1], test_array);
+
+    // Three-way rotation.
+    let (a, b, c) = test_array.mut_triple(0, 1, 2).unwrap();
+    let t = *a;
+    *a = *b;
+    *b = *c;
+    *c = t;
+    assert_eq!([2, 3, 4, 1], test_array);
+
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 1,
+        }),
+        test_array.mut_pair(1, 1),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 2,
+            index: 9,
+            length: 4,
+        }),
+        test_array.mut_triple(0, 1, 9),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_partial() {
+    use alloc::vec;
+
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let (found, errors) = test_array.mut_elems_partial(&[2, 7, 0, 2, 3]);
+    assert_eq!(
+        vec![
+            MutElemsError::IndexBound {
+                position: 1,
+                index: 7,
+                length: 4,
+            },
+            MutElemsError::IndicesOverlap {
+                first: 0,
+                second: 3,
+                index: 2,
+            },
+        ],
+        errors,
+    );
+    assert_eq!(
+        vec![0, 2, 4],
+        found.iter().map(|(p, _)| *p).collect::<Vec<_>>()
+    );
+    for (_, e) in found {
+        *e += 10;
+    }
+    assert_eq!([11, 2, 13, 14], test_array);
+
+    let (found, errors) = test_array.mut_elems_partial(&[]);
+    assert!(found.is_empty() && errors.is_empty());
+}
+
+#[test]
+fn test_zero_sized() {
+    let mut test_array = [(); 4];
+
+    let es = test_array.mut_elems(&[3, 0, 2]).unwrap();
+    assert_eq!(3, es.len());
+    let [a, b, c] = es;
+    *a = ();
+    *b = ();
+    *c = ();
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 1,
+        }),
+        test_array.mut_elems(&[1, 2, 1]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 4,
+            length: 4,
+        }),
+        test_array.mut_elems(&[4]),
+    );
+
+    let es = test_array.as_mut_elems();
+    for e in es {
+        *e = ();
+    }
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut test_vec = alloc::vec![(); 5];
+        let es = test_vec.as_mut_elems_vec();
+        assert_eq!(5, es.len());
+        for e in es {
+            *e = ();
+        }
+        assert_eq!(2, test_vec.mut_elems_slice(&[4, 0]).unwrap().len());
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_mut_elems_map() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let mut es = test_array.mut_elems_map(&[3, 0]).unwrap();
+    assert_eq!(2, es.len());
+    **es.get_mut(&3).unwrap() = 7;
+    **es.get_mut(&0).unwrap() = 5;
+    assert!(!es.contains_key(&1));
+    assert_eq!([5, 2, 3, 7], test_array);
+
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 1,
+            second: 2,
+            index: 0,
+        }),
+        test_array.mut_elems_map(&[2, 0, 0]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 4,
+            length: 4,
+        }),
+        test_array.mut_elems_map(&[4]),
+    );
+}
+
+#[cfg(feature = "smallvec")]
+#[test]
+fn test_as_mut_elems_smallvec() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let mut es = test_array.as_mut_elems_smallvec::<4>();
+    assert!(!es.spilled());
+    *es[1] = 5;
+    *es[3] = 7;
+    drop(es);
+    assert_eq!([1, 5, 3, 7], test_array);
+
+    let es = test_array.as_mut_elems_smallvec::<2>();
+    assert!(es.spilled());
+    assert_eq!(4, es.len());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_dedup() {
+    use alloc::vec;
+
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let es = test_array.mut_elems_dedup(&[2, 0, 2, 3, 0, 2]).unwrap();
+    assert_eq!(vec![&3, &1, &4], es);
+    for e in es {
+        *e += 10;
+    }
+    assert_eq!([11, 2, 13, 14], test_array);
+
+    assert!(test_array.mut_elems_dedup(&[]).unwrap().is_empty());
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 2,
+            index: 4,
+            length: 4,
+        }),
+        test_array.mut_elems_dedup(&[1, 1, 4]),
+    );
+}
+
+#[test]
+fn test_mut_indexed() {
+    let mut test_array = [0u8, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+
+    let (a,) = test_array.mut_indexed((4,)).unwrap();
+    assert_eq!(4, *a);
+
+    let (a, b) = (3, 1).mut_elems_of(&mut test_array).unwrap();
+    core::mem::swap(a, b);
+    assert_eq!([0, 3, 2, 1], test_array[..4]);
+
+    let t = test_array
+        .mut_indexed((11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0))
+        .unwrap();
+    *t.11 = 100;
+    assert_eq!(100, test_array[0]);
+
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 5,
+        }),
+        test_array.mut_indexed((5, 6, 5)),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 12,
+            length: 12,
+        }),
+        test_array.mut_indexed((0, 12)),
+    );
+}
+
+#[test]
+fn test_chunks_disjoint_mut() {
+    let mut test_array = [1u8, 2, 3, 4, 5, 6, 7];
+
+    let [a, b, c] = test_array.chunks_disjoint_mut();
+    assert_eq!([1, 2], a);
+    assert_eq!([3, 4], b);
+    assert_eq!([5, 6, 7], c);
+    a[0] = 10;
+    c[2] = 70;
+    assert_eq!([10, 2, 3, 4, 5, 6, 70], test_array);
+
+    let [a, b] = test_array[..1].chunks_disjoint_mut();
+    assert!(a.is_empty());
+    assert_eq!([10], b);
+
+    let [a] = test_array.chunks_disjoint_mut();
+    assert_eq!(7, a.len());
+    let [] = test_array.chunks_disjoint_mut::<0>();
+}
+
+#[test]
+fn test_error_kind() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let kind = |r: Result<[&mut u8; 2], MutElemsError>| r.unwrap_err().kind();
+    assert_eq!(
+        MutElemsErrorKind::Overlap,
+        kind(test_array.mut_elems(&[1, 1]))
+    );
+    assert_eq!(
+        MutElemsErrorKind::OutOfBounds,
+        kind(test_array.mut_elems(&[1, 4])),
+    );
+    assert_eq!(
+        MutElemsErrorKind::Unsorted,
+        kind(test_array.mut_elems_sorted(&[2, 1])),
+    );
+    assert_eq!(
+        MutElemsErrorKind::OutOfBounds,
+        test_array.mut_ranges(&[0..1, 2..5]).unwrap_err().kind(),
+    );
+
+    // Display messages are unaffected.
+    #[cfg(feature = "alloc")]
+    {
+        use alloc::string::ToString;
+
+        assert_eq!(
+            "indices 0 and 1 are both 1",
+            test_array.mut_elems(&[1, 1]).unwrap_err().to_string(),
+        );
+    }
+}
+
+#[test]
+fn test_mut_elems_array() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let es = <[u8; 4] as MutElemsExt<u8>>::mut_elems(&mut test_array, &[3, 0, 1]).unwrap();
+    assert_eq!([&4, &1, &2], es);
+    *es[0] = 7;
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 4,
+            length: 4,
+        }),
+        <[u8; 4] as MutElemsExt<u8>>::mut_elems(&mut test_array, &[0, 4]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 2,
+        }),
+        test_array.mut_elems(&[2, 3, 2]),
+    );
+
+    // Delegated methods behave as for slices.
+    let [a, b] = test_array.mut_ranges(&[0..1, 3..4]).unwrap();
+    core::mem::swap(&mut a[0], &mut b[0]);
+    assert_eq!([7, 2, 3, 1], test_array);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_as_mut_elems_array() {
+    use alloc::vec;
+
+    let mut test_vec = vec![1u8, 2, 3, 4];
+    let es: [&mut u8; 4] = test_vec.as_mut_elems_array().unwrap();
+    assert_eq!([&1, &2, &3, &4], es);
+    *es[1] = 5;
+    *es[3] = 7;
+    assert_eq!(vec![1, 5, 3, 7], test_vec);
+
+    let err = test_vec.as_mut_elems_array::<3>().unwrap_err();
+    assert_eq!(
+        MutElemsError::LengthMismatch {
+            expected: 3,
+            actual: 4,
+        },
+        err,
+    );
+    assert_eq!(MutElemsErrorKind::Length, err.kind());
+
+    let mut empty: Vec<u8> = vec![];
+    assert_eq!(Ok([]), empty.as_mut_elems_array::<0>());
+}
+
+#[test]
+fn test_mut_elems_cross() {
+    let mut front = [1u8, 2, 3, 4];
+    let mut back = [5u8, 6];
+
+    let ([f1, f3], [b1, b0]) = front.mut_elems_cross(&[1, 3], &mut back, &[1, 0]).unwrap();
+    core::mem::swap(f1, b1);
+    core::mem::swap(f3, b0);
+    assert_eq!([1, 6, 3, 5], front);
+    assert_eq!([4, 2], back);
+
+    // The same index may be used in both slices.
+    assert!(front.mut_elems_cross(&[0], &mut back, &[0]).is_ok());
+
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 2,
+        }),
+        front.mut_elems_cross(&[2, 2], &mut back, &[0]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 2,
+            length: 2,
+        }),
+        front.mut_elems_cross(&[0, 1], &mut back, &[2]),
+    );
+}
+
+#[test]
+fn test_check_indices_linear() {
+    assert_eq!(Ok(()), check_indices_linear(&[3, 1, 2, 0], 4));
+    // Same report as the sort-based check.
+    for indices in [[1, 2, 2, 1], [0, 1, 2, 0], [4, 4, 4, 4]] {
+        let mut pairs: [(usize, usize); 4] = core::array::from_fn(|i| (indices[i], i));
+        assert_eq!(find_overlap(&mut pairs), check_indices_linear(&indices, 5));
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_verbose() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let [a, b] = test_array.mut_elems_verbose(&[3, 1]).unwrap();
+    core::mem::swap(a, b);
+    assert_eq!([1, 4, 3, 2], test_array);
+
+    let err = test_array.mut_elems_verbose(&[0, 2, 0]).unwrap_err();
+    assert_eq!(
+        MutElemsError::WithIndices {
+            error: Box::new(MutElemsError::IndicesOverlap {
+                first: 0,
+                second: 2,
+                index: 0,
+            }),
+            indices: Box::new([0, 2, 0]),
+        },
+        err,
+    );
+    assert_eq!(MutElemsErrorKind::Overlap, err.kind());
+}
+
+#[test]
+fn test_mut_elems_rest() {
+    let mut test_array = [1u8, 2, 3, 4, 5];
+
+    let ([a, b], rest) = test_array.mut_elems_rest(&[4, 1]).unwrap();
+    assert_eq!(3, rest.len());
+    core::mem::swap(a, b);
+    for (e, x) in rest.zip([1, 3, 4]) {
+        assert_eq!(x, *e);
+        *e -= x;
+    }
+    assert_eq!([0, 5, 0, 0, 2], test_array);
+
+    // No indices: the rest is everything.
+    let ([], rest) = test_array.mut_elems_rest(&[]).unwrap();
+    assert_eq!(5, rest.len());
+    assert!(rest.map(|e| *e).eq([0, 5, 0, 0, 2]));
+
+    // All indices: the rest is empty.
+    let (es, mut rest) = test_array.mut_elems_rest(&[3, 0, 4, 1, 2]).unwrap();
+    assert_eq!([&0, &0, &2, &5, &0], es);
+    assert_eq!(0, rest.len());
+    assert!(rest.next().is_none());
+
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 5,
+            length: 5,
+        }),
+        test_array.mut_elems_rest(&[0, 5]).map(|(es, _)| es),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_boxed_slice() {
+    use alloc::vec;
+
+    let mut boxed: Box<[u8]> = vec![1u8, 2, 3, 4].into_boxed_slice();
+
+    let [a, b] = boxed.mut_elems(&[0, 3]).unwrap();
+    core::mem::swap(a, b);
+    assert_eq!([4, 2, 3, 1], *boxed);
+
+    // Through a reference to the box.
+    let boxed_ref: &mut Box<[u8]> = &mut boxed;
+    let [c] = boxed_ref.mut_elems(&[1]).unwrap();
+    *c = 5;
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 4,
+            length: 4,
+        }),
+        boxed_ref.mut_elems(&[4]),
+    );
+    let [_, x, y, _] = boxed_ref.as_mut_elems_array().unwrap();
+    core::mem::swap(x, y);
+    assert_eq!(
+        vec![&mut 4, &mut 3, &mut 5, &mut 1],
+        boxed_ref.as_mut_elems_vec()
+    );
+}
+
+#[test]
+fn test_mut_elems_wrapped() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let mut es = test_array.mut_elems_wrapped(&[2, 0, 3]).unwrap();
+    assert_eq!(3, es.len());
+    assert!(!es.is_empty());
+    assert_eq!(3, es[0]);
+    es[1] = 5;
+    assert_eq!(Some(&&mut 4), es.last());
+    for e in es.iter_mut() {
+        **e += 1;
+    }
+    let total: u8 = es.into_iter().map(|e| *e).sum();
+    assert_eq!(15, total);
+    assert_eq!([6, 2, 4, 5], test_array);
+
+    let es = test_array.mut_elems_wrapped(&[]).unwrap();
+    assert!(es.is_empty());
+    assert_eq!(0, es.into_inner().len());
+
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 1,
+        }),
+        test_array
+            .mut_elems_wrapped(&[1, 1])
+            .map(MutElems::into_inner),
+    );
+}
+
+#[test]
+fn test_mut_elems_maybe_uninit() {
+    use core::mem::MaybeUninit;
+
+    let mut slots = [const { MaybeUninit::<u8>::uninit() }; 4];
+    let [a, b]: [&mut MaybeUninit<u8>; 2] = slots.mut_elems(&[3, 0]).unwrap();
+    a.write(4);
+    b.write(1);
+    let [c, d] = slots.mut_elems(&[1, 2]).unwrap();
+    c.write(2);
+    d.write(3);
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 2,
+        }),
+        slots.mut_elems(&[2, 2]).map(|_| ()),
+    );
+
+    // Safety: Every slot has been written.
+    let values = slots.map(|e| unsafe { e.assume_init() });
+    assert_eq!([1, 2, 3, 4], values);
+}
+
+#[test]
+fn test_mut_elems_checked_overlap_only() {
+    let mut test_array = [1u8, 2, 3, 4, 5, 6];
+
+    // Safety: All indices are in bounds.
+    let es = unsafe { test_array.mut_elems_checked_overlap_only(&[5, 0, 2, 3, 1]) }.unwrap();
+    *es[0] = 7;
+    assert_eq!([&7, &1, &3, &4, &2], es);
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 1,
+            second: 3,
+            index: 4,
+        }),
+        // Safety: All indices are in bounds.
+        unsafe { test_array.mut_elems_checked_overlap_only(&[0, 4, 1, 4, 2]) },
+    );
+
+    // `usize::MAX - 1` is in bounds for a long enough slice
+    // of zero-sized elements, and is checked like any other.
+    const M: usize = usize::MAX;
+    let mut zsts = [(); M];
+    // Safety: All indices are in bounds.
+    assert!(unsafe { zsts.mut_elems_checked_overlap_only(&[M - 1, 0, M - 2]) }.is_ok());
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: M - 1,
+        }),
+        // Safety: All indices are in bounds.
+        unsafe { zsts.mut_elems_checked_overlap_only(&[M - 1, 0, M - 1]) },
+    );
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn test_mut_elems_checked_overlap_only_bounds() {
+    let mut test_array = [1u8, 2, 3];
+    // Safety: The debug assertion catches the bad index
+    // before it is used.
+    let _ = unsafe { test_array.mut_elems_checked_overlap_only(&[0, 3]) };
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn test_mut_elems_view() {
+    use alloc::vec;
+    use ndarray::{s, Array1};
+
+    let mut test_array = Array1::from(vec![1u8, 2, 3, 4, 5, 6, 7]);
+
+    // A contiguous array.
+    let [a, b] = test_array.mut_elems(&[6, 0]).unwrap();
+    core::mem::swap(a, b);
+    assert_eq!(vec![7, 2, 3, 4, 5, 6, 1], test_array.to_vec());
+
+    // A strided view.
+    let mut view = test_array.slice_mut(s![1..;3]);
+    assert_eq!(2, view.len());
+    let [a, b] = view.mut_elems(&[0, 1]).unwrap();
+    *a = 0;
+    *b = 0;
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 2,
+            length: 2,
+        }),
+        view.mut_elems(&[2]),
+    );
+    assert_eq!(vec![7, 0, 3, 4, 0, 6, 1], test_array.to_vec());
+
+    // A reversed view has a negative stride.
+    let mut view = test_array.slice_mut(s![..;-2]);
+    let [a, b, c] = view.mut_elems(&[0, 3, 1]).unwrap();
+    assert_eq!([&mut 1, &mut 7, &mut 0], [&mut *a, &mut *b, &mut *c]);
+    *a = 9;
+    *c = 8;
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 2,
+        }),
+        view.mut_elems(&[2, 2]),
+    );
+    assert_eq!(vec![7, 0, 3, 4, 8, 6, 9], test_array.to_vec());
+}
+
+#[test]
+fn test_mut_elems_request() {
+    let request = MutElemsRequest::new([3, 1]).unwrap();
+    assert_eq!(&[3, 1], request.indices());
+
+    let mut test_array = [1u8, 2, 3, 4];
+    let [a, b] = request.apply(&mut test_array).unwrap();
+    core::mem::swap(a, b);
+    assert_eq!([1, 4, 3, 2], test_array);
+
+    let mut short = [1u8, 2, 3];
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 3,
+            length: 3,
+        }),
+        request.apply(&mut short),
+    );
+
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 5,
+        }),
+        MutElemsRequest::new([5, 0, 5]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 1,
+            second: 2,
+            index: 0,
+        }),
+        MutElemsRequest::new([usize::MAX, 0, 0]),
+    );
+    // Bounds are left to `apply()`.
+    let request = MutElemsRequest::new([usize::MAX, 0]).unwrap();
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: usize::MAX,
+            length: 4,
+        }),
+        request.apply(&mut test_array),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_ordered() {
+    use alloc::vec;
+
+    let mut test_array = [1u8, 2, 3, 4, 5];
+    let indices = [4, 1, 3, 0];
+
+    let (mut es, perm) = test_array.mut_elems_ordered(&indices).unwrap();
+    assert_eq!(vec![&1, &2, &4, &5], es);
+    assert_eq!(vec![3, 1, 2, 0], perm);
+    for (e, &p) in es.iter_mut().zip(&perm) {
+        assert_eq!(indices[p] as u8 + 1, **e);
+        **e = p as u8;
+    }
+    assert_eq!([3, 1, 3, 2, 0], test_array);
+
+    let (es, perm) = test_array.mut_elems_ordered(&[]).unwrap();
+    assert!(es.is_empty() && perm.is_empty());
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 1,
+        }),
+        test_array.mut_elems_ordered(&[1, 2, 1]),
+    );
+}
+
+#[test]
+fn test_mut_elems_huge_indices() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    for ix in [usize::MAX, usize::MAX / 2, isize::MAX as usize + 1] {
+        assert_eq!(
+            Err(MutElemsError::IndexBound {
+                position: 1,
+                index: ix,
+                length: 4,
+            }),
+            test_array.mut_elems(&[0, ix, 1]),
+        );
+        assert_eq!(None, test_array.mut_elems_opt(&[ix]));
+        assert!(test_array.mut_ranges(&[0..1, ix..ix]).is_err());
+        assert!(test_array.mut_ranges(&[1..ix, 0..1]).is_err());
+        assert!(test_array.mut_elems_sorted(&[0, ix]).is_err());
+    }
+    assert!(test_array.mut_elems_generic(&[u64::MAX]).is_err());
+    assert!(test_array
+        .mut_elems_signed(&[isize::MIN, isize::MAX])
+        .is_err());
+
+    // Indices near `usize::MAX` are in bounds for a long
+    // enough slice of zero-sized elements.
+    let mut zsts = [(); usize::MAX];
+    assert!(zsts.mut_elems(&[usize::MAX - 1, 0, usize::MAX - 2]).is_ok());
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: usize::MAX,
+            length: usize::MAX,
+        }),
+        zsts.mut_elems(&[usize::MAX]),
+    );
+}
+
+#[test]
+fn test_mut_elems_huge_indices_bitset() {
+    // Enough indices to be checked with a bitset over their
+    // span, whose window would reach past `usize::MAX`.
+    const M: usize = usize::MAX;
+    let mut zsts = [(); M];
+    assert!(zsts.mut_elems(&[M - 2, M - 3, M - 4, M - 5, M - 6]).is_ok());
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 4,
+            index: M - 2,
+        }),
+        zsts.mut_elems(&[M - 2, M - 3, M - 4, M - 5, M - 2]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 4,
+            index: M,
+            length: M,
+        }),
+        zsts.mut_elems(&[M - 2, M - 3, M - 4, M - 5, M]),
+    );
+    #[cfg(feature = "alloc")]
+    assert!(zsts[..]
+        .mut_elems_slice(&[M - 2, M - 3, M - 4, M - 5, M - 6])
+        .is_ok());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_and_metadata() {
+    let mut test_vec: Vec<u8> = Vec::with_capacity(8);
+    test_vec.extend([1, 2, 3, 4]);
+    let capacity = test_vec.capacity();
+
+    let ([a, b], meta) = test_vec.mut_elems_and_metadata(&[3, 0]).unwrap();
+    assert_eq!(VecMetadata { len: 4, capacity }, meta);
+    *a = meta.len as u8;
+    *b = 0;
+    assert_eq!([0, 2, 3, 4], *test_vec);
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 4,
+            length: 4,
+        }),
+        test_vec.mut_elems_and_metadata(&[4]).map(|(_, meta)| meta),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_masked() {
+    use alloc::vec;
+
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let es = test_array
+        .mut_elems_masked(&[false, true, true, false])
+        .unwrap();
+    assert_eq!(vec![&2, &3], es);
+    for e in es {
+        *e = 0;
+    }
+    assert_eq!([1, 0, 0, 4], test_array);
+    assert!(test_array.mut_elems_masked(&[false; 4]).unwrap().is_empty());
+
+    assert_eq!(
+        Err(MutElemsError::LengthMismatch {
+            expected: 4,
+            actual: 3,
+        }),
+        test_array.mut_elems_masked(&[true; 3]),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_windows_disjoint_mut() {
+    use alloc::vec;
+
+    let mut test_array = [1u8, 2, 3, 4, 5, 6, 7];
+
+    let ws = test_array.windows_disjoint_mut(&[4, 0, 2], 2).unwrap();
+    assert_eq!(vec![&mut [5, 6][..], &mut [1, 2], &mut [3, 4]], ws);
+    let mut ws = ws.into_iter();
+    let (a, b) = (ws.next().unwrap(), ws.next().unwrap());
+    a.swap_with_slice(b);
+    assert_eq!([5, 6, 3, 4, 1, 2, 7], test_array);
+
+    // Empty windows overlap nothing.
+    assert_eq!(
+        3,
+        test_array
+            .windows_disjoint_mut(&[3, 3, 7], 0)
+            .unwrap()
+            .len()
+    );
+
+    assert_eq!(
+        Err(MutElemsError::RangesOverlap {
+            first: 1,
+            second: 2,
+        }),
+        test_array.windows_disjoint_mut(&[2, 5, 4], 2),
+    );
+    assert_eq!(
+        Err(MutElemsError::RangeBound {
+            position: 1,
+            start: 5,
+            end: 8,
+            length: 7,
+        }),
+        test_array.windows_disjoint_mut(&[0, 5], 3),
+    );
+    assert_eq!(
+        Err(MutElemsError::RangeBound {
+            position: 0,
+            start: usize::MAX,
+            end: usize::MAX,
+            length: 7,
+        }),
+        test_array.windows_disjoint_mut(&[usize::MAX], 2),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_boxed() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let es = test_array.mut_elems_boxed(&[3, 1]).unwrap();
+    assert_eq!(2, es.len());
+    *es[0] = 7;
+    *es[1] = 5;
+    assert_eq!([1, 5, 3, 7], test_array);
+    assert!(test_array.mut_elems_boxed(&[]).unwrap().is_empty());
+
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 4,
+            length: 4,
+        }),
+        test_array.mut_elems_boxed(&[0, 4]),
+    );
+}
+
+#[test]
+fn test_mut_elems_empty() {
+    let empty: &mut [u8] = &mut [];
+    assert_eq!(Ok([]), empty.mut_elems(&[]));
+    assert_eq!(Some([]), empty.mut_elems_opt(&[]));
+    assert_eq!(Ok([]), empty.mut_elems_sorted(&[]));
+    for ix in [0, 1, usize::MAX] {
+        assert_eq!(
+            Err(MutElemsError::IndexBound {
+                position: 0,
+                index: ix,
+                length: 0,
+            }),
+            empty.mut_elems(&[ix]),
+        );
+    }
+    assert!(empty.mut_elems(&[0, 0]).is_err());
+    assert_eq!(Ok([&mut [][..], &mut []]), empty.mut_ranges(&[0..0, 0..0]));
+
+    let mut test_array = [1u8, 2, 3];
+    assert_eq!(Ok([]), test_array.mut_elems(&[]));
+    assert_eq!(Ok([]), test_array.as_mut_slice().mut_elems(&[]));
+    assert_eq!([1, 2, 3], test_array);
+}
+
+#[test]
+fn test_mut_elems_tagged() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let es = test_array.mut_elems_tagged(&[2, 0, 3]).unwrap();
+    assert_eq!([(2, &mut 3), (0, &mut 1), (3, &mut 4)], es);
+    for (ix, e) in es {
+        *e += ix as u8;
+    }
+    assert_eq!([1, 2, 5, 7], test_array);
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 1,
+        }),
+        test_array.mut_elems_tagged(&[1, 1]),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_elems_allowing_dup() {
+    let test_array = [Cell::new(1u8), Cell::new(2), Cell::new(3)];
+
+    let es = test_array.elems_allowing_dup(&[2, 0, 2]).unwrap();
+    assert_eq!(3, es.len());
+    es[0].set(7);
+    assert_eq!(7, es[2].get());
+    es[1].swap(es[2]);
+    assert!(test_array.iter().map(Cell::get).eq([7, 2, 1]));
+    assert!(test_array.elems_allowing_dup(&[]).unwrap().is_empty());
+
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 3,
+            length: 3,
+        }),
+        test_array.elems_allowing_dup(&[1, 3]),
+    );
+}
+
+#[cfg(all(feature = "trace", feature = "std"))]
+#[test]
+fn test_trace_strategy() {
+    use std::string::{String, ToString};
+    use std::sync::Mutex;
+
+    // Other tests may log concurrently, so only look for the
+    // expected messages among whatever is logged.
+    struct TestLogger(Mutex<Vec<String>>);
+
+    impl log::Log for TestLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: TestLogger = TestLogger(Mutex::new(Vec::new()));
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let mut test_vec = alloc::vec![0u8; BITSET_LIMIT + 1];
+    let _ = test_vec.mut_elems(&[0, 1]);
+    let _ = test_vec[..8].mut_elems(&[0, 1, 2, 3, 4, 5]);
+    let _ = test_vec.mut_elems(&[1, 2, 3, 4, 5, 6]);
+    let _ = test_vec.mut_elems(&[0, 1, 2, 3, 4, BITSET_LIMIT]);
+    let _ = test_vec.mut_elems_slice(&[0, 100, 1, 2, 3, 4, BITSET_LIMIT]);
+
+    let messages = LOGGER.0.lock().unwrap();
+    for expected in [
+        "checking 2 indices against length 4097: linear",
+        "checking 6 indices against length 8: bitset",
+        "checking 6 indices against length 4097: bitset",
+        "checking 6 indices against length 4097: sort",
+        "checking 7 indices against length 4097: sort on stack",
+    ] {
+        assert!(messages.iter().any(|m| m == expected), "{expected}");
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_mut_elems_stride() {
+    use alloc::vec;
+
+    let mut test_array = [1u8, 2, 3, 4, 5, 6, 7];
+
+    assert_eq!(vec![&2, &5], test_array.mut_elems_stride(1, 3, 2).unwrap());
+    assert_eq!(
+        vec![&1, &4, &7],
+        test_array.mut_elems_stride(0, 3, 3).unwrap()
+    );
+    assert_eq!(vec![&7], test_array.mut_elems_stride(6, 0, 1).unwrap());
+    assert!(test_array.mut_elems_stride(9, 1, 0).unwrap().is_empty());
+    for e in test_array.mut_elems_stride(0, 2, 4).unwrap() {
+        *e = 0;
+    }
+    assert_eq!([0, 2, 0, 4, 0, 6, 0], test_array);
+
+    // Each error agrees with `mut_elems_slice()` on the same
+    // indices.
+    for (start, step, count, indices) in [
+        (0, 3, 4, &[0, 3, 6, 9][..]),
+        (2, 0, 2, &[2, 2]),
+        (7, 0, 2, &[7, 7]),
+        (1, 1, 7, &[1, 2, 3, 4, 5, 6, 7]),
+    ] {
+        let err = test_array.mut_elems_slice(indices).unwrap_err();
+        assert_eq!(Err(err), test_array.mut_elems_stride(start, step, count));
+    }
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: usize::MAX,
+            length: 7,
+        }),
+        test_array.mut_elems_stride(1, usize::MAX, 2),
+    );
+}
+
+#[test]
+fn test_mut_swap_if() {
+    let mut test_array = [4u8, 3, 2, 1];
+
+    // A sorting network for four elements.
+    for (i, j) in [(0, 1), (2, 3), (0, 2), (1, 3), (1, 2)] {
+        test_array.mut_swap_if(i, j, |a, b| a > b).unwrap();
+    }
+    assert_eq!([1, 2, 3, 4], test_array);
+
+    assert_eq!(Ok(false), test_array.mut_swap_if(3, 0, |_, _| false));
+    assert_eq!(
+        Ok(true),
+        test_array.mut_swap_if(3, 0, |&a, &b| (a, b) == (4, 1))
+    );
+    assert_eq!([4, 2, 3, 1], test_array);
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 1,
+        }),
+        test_array.mut_swap_if(1, 1, |_, _| true),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 4,
+            length: 4,
+        }),
+        test_array.mut_swap_if(0, 4, |_, _| true),
+    );
+}
+
+#[test]
+fn test_mut_elems_grouped() {
+    let mut test_array = [1u8, 2, 3, 4, 5, 6, 7];
+
+    let [[a, b, c], [d, e, f]] = test_array
+        .mut_elems_grouped(&[[6, 0, 1], [2, 3, 5]])
+        .unwrap();
+    for (x, y) in [(a, d), (b, e), (c, f)] {
+        core::mem::swap(x, y);
+    }
+    assert_eq!([4, 6, 7, 1, 5, 2, 3], test_array);
+    assert_eq!(Ok([]), test_array.mut_elems_grouped::<0, 2>(&[]));
+    assert_eq!(
+        Ok([[], []]),
+        test_array.mut_elems_grouped::<2, 0>(&[[], []])
+    );
+
+    // Overlap across groups, at flattened positions.
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 1,
+            second: 4,
+            index: 0,
+        }),
+        test_array.mut_elems_grouped(&[[6, 0], [1, 2], [0, 3]]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 3,
+            index: 7,
+            length: 7,
+        }),
+        test_array.mut_elems_grouped(&[[0, 1], [2, 7]]),
+    );
+}
+
+#[cfg(feature = "arrayvec")]
+#[test]
+fn test_mut_elems_arrayvec() {
+    let mut test_array = [1u8, 2, 3, 4];
+
+    let mut es = test_array.mut_elems_arrayvec::<3>(&[2, 0, 3]).unwrap();
+    assert_eq!(3, es.len());
+    *es[1] = 5;
+    assert_eq!([&3, &5, &4], es.as_slice());
+    drop(es);
+    assert_eq!([5, 2, 3, 4], test_array);
+    assert!(test_array.mut_elems_arrayvec::<0>(&[]).unwrap().is_empty());
+
+    let err = test_array.mut_elems_arrayvec::<2>(&[2, 0, 3]).unwrap_err();
+    assert_eq!(
+        MutElemsError::CapacityExceeded {
+            count: 3,
+            capacity: 2,
+        },
+        err,
+    );
+    assert_eq!(MutElemsErrorKind::Length, err.kind());
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 2,
+        }),
+        test_array.mut_elems_arrayvec::<4>(&[2, 2]),
+    );
+}
+
+#[test]
+fn test_sort_net() {
+    fn check<const N: usize>() {
+        // By the 0-1 principle, a network that sorts every
+        // array of 0s and 1s sorts every array.
+        for bits in 0..1u32 << N {
+            let mut a: [u32; N] = core::array::from_fn(|i| (bits >> i) & 1);
+            let mut expected = a;
+            expected.sort();
+            a.sort_net();
+            assert_eq!(expected, a, "N = {N}, bits = {bits:#b}");
+        }
+        let mut a: [usize; N] = core::array::from_fn(|i| (i * 5 + 3) % N.max(1));
+        let mut expected = a;
+        expected.sort();
+        a.sort_net();
+        assert_eq!(expected, a);
+    }
+    check::<0>();
+    check::<1>();
+    check::<2>();
+    check::<3>();
+    check::<4>();
+    check::<5>();
+    check::<6>();
+    check::<7>();
+    check::<8>();
+    check::<9>();
+    check::<12>();
+}
+
+#[cfg(all(test, feature = "alloc"))]
+/// Fill `indices` with distinct pseudo-random indices less
+/// than `length`, in pseudo-random order.
+fn shuffled_indices(indices: &mut [usize], length: usize, seed: &mut u64) {
+    let mut next = || {
+        // xorshift64
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 7;
+        *seed ^= *seed << 17;
+        *seed as usize
+    };
+    for i in 0..indices.len() {
+        loop {
+            let ix = next() % length;
+            if !indices[..i].contains(&ix) {
+                indices[i] = ix;
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_mut_elems_input_order() {
+    use alloc::vec::Vec;
+
+    // Whatever overlap check is used for a given count and
+    // length, the `k`th reference must be to element
+    // `indices[k]`.
+    fn check<const N: usize>(length: usize, seed: &mut u64) {
+        let mut v: Vec<usize> = (0..length).collect();
+        for _ in 0..16 {
+            let mut indices = [0; N];
+            shuffled_indices(&mut indices, length, seed);
+            let es = v.mut_elems(&indices).unwrap();
+            for (k, e) in es.into_iter().enumerate() {
+                assert_eq!(indices[k], *e, "N = {N}, length = {length}");
+            }
+            let es = v.mut_elems_slice(&indices).unwrap();
+            for (k, e) in es.into_iter().enumerate() {
+                assert_eq!(indices[k], *e, "N = {N}, length = {length}");
+            }
+            let mut sorted = indices;
+            sorted.sort_unstable();
+            let es = v.mut_elems_sorted(&sorted).unwrap();
+            for (k, e) in es.into_iter().enumerate() {
+                assert_eq!(sorted[k], *e, "N = {N}, length = {length}");
+            }
+        }
+    }
+    let mut seed = 0x2545_f491_4f6c_dd1d;
+    for length in [64, 4096, 70_000] {
+        check::<1>(length, &mut seed);
+        check::<2>(length, &mut seed);
+        check::<3>(length, &mut seed);
+        check::<4>(length, &mut seed);
+        check::<5>(length, &mut seed);
+        check::<8>(length, &mut seed);
+        check::<17>(length, &mut seed);
+        check::<32>(length, &mut seed);
+        check::<33>(length, &mut seed);
+        check::<64>(length, &mut seed);
+    }
+}
+
+#[test]
+fn test_mut_pixels() {
+    let mut image = [[0u8; 4]; 3];
+    let [a, b, c] = image.mut_pixels(&[(2, 3), (0, 0), (1, 2)]).unwrap();
+    *a = 1;
+    *b = 2;
+    *c = 3;
+    assert_eq!([[2, 0, 0, 0], [0, 0, 3, 0], [0, 0, 0, 1]], image);
+
+    assert_eq!(
+        Err(MutElemsError::Index2DBound {
+            position: 1,
+            row: 0,
+            col: 4,
+            rows: 3,
+            cols: 4,
+        }),
+        image.mut_pixels(&[(0, 0), (0, 4)]),
+    );
+    let e = image.mut_pixels(&[(1, 0), (3, 0)]).unwrap_err();
+    assert_eq!(MutElemsErrorKind::OutOfBounds, e.kind());
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 6,
+        }),
+        image.mut_pixels(&[(1, 2), (0, 1), (1, 2)]),
+    );
+}
+
+#[test]
+fn test_scatter_accumulate() {
+    let mut v = [0usize; 5];
+    v.scatter_accumulate(&[4, 0, 4, 2, 4], |e, position| *e += position)
+        .unwrap();
+    assert_eq!([1, 0, 3, 0, 6], v);
+
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 2,
+            index: 5,
+            length: 5,
+        }),
+        v.scatter_accumulate(&[0, 1, 5], |e, _| *e = 9),
+    );
+    assert_eq!([1, 0, 3, 0, 6], v);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_mut_elems_refmut() {
+    use alloc::vec;
+    use core::cell::RefCell;
+
+    let cell = RefCell::new(vec![1u8, 2, 3]);
+    let mut guard = cell.borrow_mut();
+    let [a, b] = guard.mut_elems(&[0, 2]).unwrap();
+    *a += 10;
+    // The cell stays borrowed while the references are held.
+    assert!(cell.try_borrow().is_err());
+    *b += 10;
+    drop(guard);
+    assert_eq!(vec![11, 2, 13], *cell.borrow());
+}
+
+#[test]
+fn test_check_distinct_bounds() {
+    assert_eq!(Ok(()), check_distinct(&[0usize; 0]));
+    assert_eq!(Ok(()), check_distinct(&[5, usize::MAX, 0, 7, 9]));
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 1,
+            second: 4,
+            index: usize::MAX,
+        }),
+        check_distinct(&[5, usize::MAX, 0, 7, usize::MAX]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 2,
+        }),
+        check_distinct(&[2, 1, 2]),
+    );
+
+    assert_eq!(Ok(()), check_bounds(&[], 0));
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 3,
+            length: 3,
+        }),
+        check_bounds(&[0, 3, 4], 3),
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_mut_elems_split() {
+    let mut v: [u8; 6] = [0, 1, 2, 3, 4, 5];
+    let (es, rest) = v.mut_elems_split(&[4, 1]).unwrap();
+    assert_eq!(6, rest.len());
+    assert!(rest.is_selected(1));
+    assert!(!rest.is_selected(2));
+    assert_eq!(None, rest.get(1));
+    assert_eq!(None, rest.get(4));
+    assert_eq!(None, rest.get(6));
+    assert_eq!(Some(&5), rest.get(5));
+    for e in es {
+        *e += rest[0] + rest[2] + rest[3] + rest[5];
+    }
+    assert_eq!([0, 11, 2, 3, 14, 5], v);
+
+    assert_eq!(
+        Some(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 2,
+        }),
+        v.mut_elems_split(&[2, 2]).err(),
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+#[should_panic(expected = "index 1 is mutably borrowed")]
+fn test_mut_elems_split_index_selected() {
+    let mut v = [0u8; 3];
+    let (_, rest) = v.mut_elems_split(&[1]).unwrap();
+    let _ = rest[1];
+}
+
+#[test]
+fn test_mut_first_last() {
+    let mut v = [1u8, 2, 3, 4, 5];
+    let [a, b, c] = v.mut_first().unwrap();
+    *a += 10;
+    *b += 10;
+    *c += 10;
+    let [d, e] = v.mut_last().unwrap();
+    *d += 20;
+    *e += 20;
+    assert_eq!([11, 12, 13, 24, 25], v);
+    assert_eq!(Ok([]), v.mut_last::<0>());
+    assert_eq!(5, v.mut_last::<5>().unwrap().len());
+
+    let expected = Err(MutElemsError::LengthMismatch {
+        expected: 6,
+        actual: 5,
+    });
+    assert_eq!(expected, v.mut_first::<6>());
+    assert_eq!(expected, v.mut_last::<6>());
+}
+
+#[test]
+fn test_mut_elems_pinned() {
+    use core::marker::PhantomPinned;
+
+    struct Unmovable {
+        value: u8,
+        _pin: PhantomPinned,
+    }
+
+    let mut a: [Unmovable; 3] = core::array::from_fn(|i| Unmovable {
+        value: i as u8,
+        _pin: PhantomPinned,
+    });
+    let slice: &mut [Unmovable] = &mut a;
+    // Safety: `a` is never moved.
+    let mut pinned = unsafe { Pin::new_unchecked(slice) };
+    let [x, y] = pinned.mut_elems_pinned(&[2, 0]).unwrap();
+    // Safety: Only a field is written; nothing is moved.
+    unsafe {
+        x.get_unchecked_mut().value += 10;
+        y.get_unchecked_mut().value += 20;
+    }
+    assert_eq!(
+        Some(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 1,
+        }),
+        pinned.mut_elems_pinned(&[1, 1]).err(),
+    );
+    let values: [u8; 3] = core::array::from_fn(|i| a[i].value);
+    assert_eq!([20, 1, 12], values);
+}
+
+#[test]
+fn test_write_elems() {
+    let mut v = [0u8; 5];
+    v.write_elems(&[4, 1, 2], [1, 2, 3]).unwrap();
+    assert_eq!([0, 2, 3, 0, 1], v);
+    v.write_elems(&[], []).unwrap();
+
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 0,
+        }),
+        v.write_elems(&[0, 3, 0], [9, 9, 9]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 5,
+            length: 5,
+        }),
+        v.write_elems(&[0, 5], [9, 9]),
+    );
+    assert_eq!([0, 2, 3, 0, 1], v);
+}
+
+#[test]
+fn test_permute_elems() {
+    let mut v = [0u8, 1, 2, 3, 4, 5];
+
+    // Identity.
+    v.permute_elems(&[5, 1, 3], &[0, 1, 2]).unwrap();
+    assert_eq!([0, 1, 2, 3, 4, 5], v);
+
+    // Reverse.
+    v.permute_elems(&[0, 2, 3, 5], &[3, 2, 1, 0]).unwrap();
+    assert_eq!([5, 1, 3, 2, 4, 0], v);
+
+    // A 3-cycle and a 2-cycle.
+    let mut v = [0u8, 1, 2, 3, 4];
+    v.permute_elems(&[0, 1, 2, 3, 4], &[1, 2, 0, 4, 3]).unwrap();
+    assert_eq!([1, 2, 0, 4, 3], v);
+
+    let e = v.permute_elems(&[0, 1, 2], &[0, 3, 1]).unwrap_err();
+    assert_eq!(MutElemsError::InvalidPermutation { position: 1 }, e);
+    assert_eq!(MutElemsErrorKind::Permutation, e.kind());
+    assert_eq!(
+        Err(MutElemsError::InvalidPermutation { position: 2 }),
+        v.permute_elems(&[0, 1, 2], &[1, 0, 1]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 2,
+        }),
+        v.permute_elems(&[2, 2], &[1, 0]),
+    );
+    assert_eq!([1, 2, 0, 4, 3], v);
+}
+
+#[test]
+fn test_check_indices_span() {
+    let low = 1 << 20;
+    let length = 1 << 21;
+    let check = |indices: &[usize; 6], length| {
+        let mut buf = [(0, 0); 6];
+        let expected = check_indices_sort(indices, length, &mut buf);
+        assert_eq!(expected, check_indices_in(indices, length));
+        assert_eq!(expected, check_indices_slice_in(indices, length));
+        expected
+    };
+
+    // Dense indices far from 0.
+    let indices = [low + 5, low + 3, low + 7, low + 3, low + 5, low];
+    assert_eq!(Some(low), bitset_low(&indices, length));
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 1,
+            second: 3,
+            index: low + 3,
+        }),
+        check(&indices, length),
+    );
+    let indices = [
+        low,
+        low + BITSET_LIMIT - 1,
+        low + 1,
+        low + 2,
+        low + 3,
+        low + 4,
+    ];
+    assert_eq!(Some(low), bitset_low(&indices, length));
+    assert_eq!(Ok(()), check(&indices, length));
+
+    // Six indices spanning three positions must repeat; the
+    // bitset finds the first repeat, with no separate check
+    // of the span needed.
+    let indices = [low + 2, low, low + 1, low + 1, low, low + 2];
+    assert_eq!(Some(low), bitset_low(&indices, length));
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 2,
+            second: 3,
+            index: low + 1,
+        }),
+        check(&indices, length),
+    );
+
+    // Too wide a span, or an index out of bounds, leaves the
+    // check to sorting.
+    let indices = [low, low + BITSET_LIMIT, low + 1, low + 2, low + 3, low + 4];
+    assert_eq!(None, bitset_low(&indices, length));
+    assert_eq!(Ok(()), check(&indices, length));
+    let indices = [low + 1, low, low + 1, low + 5, low + 2, low + 3];
+    assert_eq!(None, bitset_low(&indices, low + 5));
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: low + 1,
+        }),
+        check(&indices, low + 5),
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_mut_elems_partition() {
+    use alloc::vec;
+
+    let mut v = [3u8, 6, 9, 12, 15];
+    let es = v.mut_elems_wrapped(&[4, 0, 3, 1]).unwrap();
+    let (even, odd) = es.partition(|e| e % 2 == 0);
+    assert_eq!(vec![&12, &6], even);
+    assert_eq!(vec![&15, &3], odd);
+
+    let es = v.mut_elems_wrapped(&[]).unwrap();
+    let (yes, no) = es.partition(|_| true);
+    assert!(yes.is_empty() && no.is_empty());
+}
+
+#[test]
+fn test_as_mut_elems_through_ref() {
+    // A `&mut [T; N]` argument reaches the `[T; N]` impl by
+    // auto-dereference, without a `mut` binding or `&mut *`.
+    fn bump(a: &mut [u8; 4]) {
+        let [w, x, y, z] = a.as_mut_elems();
+        core::mem::swap(w, z);
+        *x += 10;
+        *y += 20;
+    }
+
+    let mut a = [1u8, 2, 3, 4];
+    bump(&mut a);
+    assert_eq!([4, 12, 23, 1], a);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_mut_elems_diagnostic() {
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    let mut a = [1u8, 2, 3, 4];
+    let [x] = a.mut_elems_diagnostic(&[3]).unwrap();
+    *x = 0;
+
+    let diagnostic = a.mut_elems_diagnostic(&[3, 1, 0, 1]).unwrap_err();
+    assert_eq!(
+        MutElemsError::IndicesOverlap {
+            first: 1,
+            second: 3,
+            index: 1,
+        },
+        diagnostic.error,
+    );
+    assert_eq!(vec![3, 1, 0, 1], diagnostic.indices);
+    assert_eq!(vec![0, 1, 1, 3], diagnostic.sorted_indices());
+    assert_eq!(
+        "indices 1 and 3 are both 1 (indices [3, 1, 0, 1])",
+        diagnostic.to_string(),
+    );
+
+    let err = MutElemsError::from(diagnostic);
+    assert_eq!(MutElemsErrorKind::Overlap, err.kind());
+    assert_eq!(
+        "indices 1 and 3 are both 1 (indices [3, 1, 0, 1])",
+        err.to_string(),
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_distinct_indices() {
+    use alloc::vec;
+
+    let indices = DistinctIndices::new(&[3, 0, usize::MAX]).unwrap();
+    assert_eq!(&[3, 0, usize::MAX], indices.indices());
+    let mut v = [1u8, 2, 3, 4];
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 2,
+            index: usize::MAX,
+            length: 4,
+        }),
+        v.mut_elems_distinct(&indices),
+    );
+
+    let indices = DistinctIndices::try_from([3, 0]).unwrap();
+    for e in v.mut_elems_distinct(&indices).unwrap() {
+        *e += 10;
+    }
+    assert_eq!([11, 2, 3, 14], v);
+    let indices = DistinctIndices::from(MutElemsRequest::new([1, 2]).unwrap());
+    assert_eq!(vec![&2, &3], v.mut_elems_distinct(&indices).unwrap());
+
+    let overlap = Err(MutElemsError::IndicesOverlap {
+        first: 0,
+        second: 2,
+        index: usize::MAX,
+    });
+    assert_eq!(overlap, DistinctIndices::new(&[usize::MAX, 1, usize::MAX]));
+    assert_eq!(
+        overlap,
+        DistinctIndices::try_from([usize::MAX, 1, usize::MAX])
+    );
+    let long: Vec<usize> = (0..40).chain([7]).collect();
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 7,
+            second: 40,
+            index: 7,
+        }),
+        DistinctIndices::try_from(&long[..]),
+    );
+}
+
+#[test]
+fn test_mut_elems_monotonic() {
+    let mut a = [0u8, 1, 2, 3, 4];
+    let [x, y] = a.mut_elems_monotonic(&[1, 4]).unwrap();
+    assert_eq!((1, 4), (*x, *y));
+    let [x, y, z] = a.mut_elems_monotonic(&[4, 2, 0]).unwrap();
+    assert_eq!((4, 2, 0), (*x, *y, *z));
+    let [x] = a.mut_elems_monotonic(&[3]).unwrap();
+    assert_eq!(3, *x);
+    assert_eq!(Ok([]), a.mut_elems_monotonic(&[]));
+
+    assert_eq!(
+        Err(MutElemsError::IndicesUnsorted { position: 2 }),
+        a.mut_elems_monotonic(&[4, 2, 3]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesUnsorted { position: 2 }),
+        a.mut_elems_monotonic(&[1, 3, 2]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 1,
+            second: 2,
+            index: 2,
+        }),
+        a.mut_elems_monotonic(&[3, 2, 2]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 5,
+            length: 5,
+        }),
+        a.mut_elems_monotonic(&[5, 2]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 5,
+            length: 5,
+        }),
+        a.mut_elems_monotonic(&[2, 5]),
+    );
+}
+
+#[test]
+fn test_mut_elems_sort_in_place() {
+    let mut a = [10u8, 11, 12, 13, 14, 15];
+    let mut indices = [5, 1, 3, 0];
+    let es = a.mut_elems_sort_in_place(&mut indices).unwrap();
+    assert_eq!([10, 11, 13, 15], es.map(|e| *e));
+    assert_eq!([0, 1, 3, 5], indices);
+
+    let mut indices = [4, 2, 4];
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 1,
+            second: 2,
+            index: 4,
+        }),
+        a.mut_elems_sort_in_place(&mut indices),
+    );
+    assert_eq!([2, 4, 4], indices);
+    let mut indices = [6, 0];
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 6,
+            length: 6,
+        }),
+        a.mut_elems_sort_in_place(&mut indices),
+    );
+}
+
+#[test]
+#[cfg(all(feature = "serde", feature = "alloc"))]
+fn test_serde() {
+    let err = MutElemsError::IndexBound {
+        position: 1,
+        index: 3,
+        length: 3,
+    };
+    let json = serde_json::to_string(&err).unwrap();
+    assert_eq!(
+        r#"{"IndexBound":{"position":1,"index":3,"length":3}}"#,
+        json
+    );
+    assert_eq!(err, serde_json::from_str(&json).unwrap());
+
+    let mut a = [1u8, 2, 3];
+    let err = a.mut_elems_verbose(&[2, 2]).unwrap_err();
+    let json = serde_json::to_string(&err).unwrap();
+    assert_eq!(
+        r#"{"WithIndices":{"error":{"IndicesOverlap":{"first":0,"second":1,"index":2}},"indices":[2,2]}}"#,
+        json,
+    );
+    assert_eq!(err, serde_json::from_str(&json).unwrap());
+
+    let kind = serde_json::to_string(&err.kind()).unwrap();
+    assert_eq!(r#""Overlap""#, kind);
+}
+
+#[test]
+fn test_error_code() {
+    let mut a = [1u8, 2, 3];
+    assert_eq!("E_OVERLAP", a.mut_elems(&[1, 1]).unwrap_err().code());
+    assert_eq!("E_BOUNDS", a.mut_elems(&[3]).unwrap_err().code());
+    assert_eq!(
+        "E_UNSORTED",
+        a.mut_elems_sorted(&[2, 1]).unwrap_err().code()
+    );
+    assert_eq!(
+        "E_RANGES_OVERLAP",
+        a.mut_ranges(&[0..2, 1..3]).unwrap_err().code()
+    );
+    assert_eq!(
+        "E_PERMUTATION",
+        a.permute_elems(&[0, 1], &[1, 1]).unwrap_err().code()
+    );
+    #[cfg(feature = "alloc")]
+    assert_eq!("E_BOUNDS", a.mut_elems_verbose(&[0, 5]).unwrap_err().code());
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_mut_elems_trait_objects() {
+    use alloc::{boxed::Box, vec, vec::Vec};
+
+    trait Shape {
+        fn area(&self) -> u32;
+        fn scale(&mut self, by: u32);
+    }
+
+    struct Square(u32);
+
+    impl Shape for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+        fn scale(&mut self, by: u32) {
+            self.0 *= by;
+        }
+    }
+
+    struct Rect(u32, u32);
+
+    impl Shape for Rect {
+        fn area(&self) -> u32 {
+            self.0 * self.1
+        }
+        fn scale(&mut self, by: u32) {
+            self.0 *= by;
+            self.1 *= by;
+        }
+    }
+
+    let mut shapes: Vec<Box<dyn Shape>> = vec![Box::new(Square(2)), Box::new(Rect(1, 3))];
+    let [r, s]: [&mut Box<dyn Shape>; 2] = shapes.mut_elems(&[1, 0]).unwrap();
+    r.scale(2);
+    s.scale(r.area() / 6);
+    assert_eq!(
+        vec![16, 12],
+        shapes.iter().map(|s| s.area()).collect::<Vec<_>>()
+    );
+    assert!(shapes.mut_elems(&[1, 1]).is_err());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_mut_elems_scoped_threads() {
+    use alloc::vec;
+
+    let mut v = vec![0u32; 8];
+    let mut es = v.mut_elems_slice(&[7, 1, 4, 2]).unwrap();
+    std::thread::scope(|s| {
+        for (i, e) in es.drain(..).enumerate() {
+            s.spawn(move || *e = i as u32 + 1);
+        }
+    });
+    assert_eq!(vec![0, 2, 4, 0, 3, 0, 0, 1], v);
+
+    // Fixed-size results distribute the same way.
+    let es = v.mut_elems(&[0, 3]).unwrap();
+    std::thread::scope(|s| {
+        for e in es {
+            s.spawn(move || *e += 10);
+        }
+    });
+    assert_eq!(vec![10, 2, 4, 10, 3, 0, 0, 1], v);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_mut_elems_with_scratch() {
+    let length = BITSET_LIMIT * 4;
+    let mut v: Vec<usize> = (0..length).collect();
+    let mut scratch = Vec::new();
+    // Sparse and long, so checked in `scratch`.
+    let sparse: [usize; 40] = core::array::from_fn(|i| (i * 7 % 40) * 397);
+    assert_eq!(None, bitset_low(&sparse, length));
+    for round in 1..=2 {
+        let es = v.mut_elems_with_scratch(&sparse, &mut scratch).unwrap();
+        for (k, e) in es.into_iter().enumerate() {
+            assert_eq!(sparse[k] + (round - 1) * length, *e);
+            *e += length;
+        }
+    }
+    assert!(scratch.capacity() >= 40);
+    // Short and dense, so checked as usual.
+    let [a, b, c] = v.mut_elems_with_scratch(&[5, 2, 9], &mut scratch).unwrap();
+    assert_eq!([5, 2, 9], [*a, *b, *c]);
+    *a = 0;
+    for (i, &e) in v.iter().enumerate() {
+        let expected = match i {
+            5 => 0,
+            i if sparse.contains(&i) => i + 2 * length,
+            i => i,
+        };
+        assert_eq!(expected, e);
+    }
+
+    let mut indices = [0; 41];
+    indices[..40].copy_from_slice(&sparse);
+    indices[40] = sparse[3];
+    let expected = v.mut_elems(&indices).map(|_| ());
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 3,
+            second: 40,
+            index: sparse[3],
+        }),
+        expected,
+    );
+    assert_eq!(
+        expected,
+        v.mut_elems_with_scratch(&indices, &mut scratch).map(|_| ())
+    );
+    indices[40] = 1 << 20;
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 40,
+            index: 1 << 20,
+            length,
+        }),
+        v.mut_elems_with_scratch(&indices, &mut scratch).map(|_| ())
+    );
+}
+
+#[test]
+fn test_mut_elems_dbg() {
+    let mut a = [1u8, 2, 3, 4];
+    // Safety: The indices are distinct and in bounds.
+    let [x, y] = unsafe { a.mut_elems_dbg(&[3, 1]) };
+    core::mem::swap(x, y);
+    assert_eq!([1, 4, 3, 2], a);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "mut_elems_dbg(): indices 0 and 2 are both 1 (indices [1, 0, 1])")]
+fn test_mut_elems_dbg_overlap() {
+    let mut a = [1u8, 2, 3, 4];
+    // Safety: This panics in debug builds, to which the test
+    // is limited.
+    let _ = unsafe { a.mut_elems_dbg(&[1, 0, 1]) };
+}
+
+#[test]
+fn test_mut_elems_soa() {
+    let mut xs = [1i32, 2, 3, 4];
+    let mut ys = [10u8, 20, 30];
+    let mut zs = [true, false, true, false, true];
+    let ([x0, x2], [y0, y2], [z0, z2]) = (&mut xs[..], &mut ys[..], &mut zs[..])
+        .mut_elems_soa(&[2, 0])
+        .unwrap();
+    *x0 += *x2;
+    *y0 += *y2;
+    core::mem::swap(z0, z2);
+    assert_eq!([1, 2, 4, 4], xs);
+    assert_eq!([10, 20, 40], ys);
+
+    // Bounds are reported against the shortest slice.
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 3,
+            length: 3,
+        }),
+        (&mut xs[..], &mut ys[..], &mut zs[..])
+            .mut_elems_soa(&[0, 3])
+            .map(|_| ()),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 1,
+        }),
+        (&mut xs[..], &mut ys[..])
+            .mut_elems_soa(&[1, 1])
+            .map(|_| ()),
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_iter_mut_except() {
+    use alloc::{vec, vec::Vec};
+
+    let mut v = [0u8; 6];
+    let it = v.iter_mut_except(&[5, 1, 1, 0]).unwrap();
+    assert_eq!(3, it.len());
+    let indices: Vec<usize> = it
+        .map(|(i, e)| {
+            *e = i as u8;
+            i
+        })
+        .collect();
+    assert_eq!(vec![2, 3, 4], indices);
+    assert_eq!([0, 0, 2, 3, 4, 0], v);
+
+    assert_eq!(6, v.iter_mut_except(&[]).unwrap().count());
+    assert_eq!(
+        Some(MutElemsError::IndexBound {
+            position: 1,
+            index: 6,
+            length: 6,
+        }),
+        v.iter_mut_except(&[0, 6]).err(),
+    );
+}
+
+#[test]
+fn test_mut_bytes_at() {
+    let mut buf = *b"a\xc3\xa9b";
+    let s = core::str::from_utf8_mut(&mut buf).unwrap();
+    // Safety: Only ASCII bytes are written, over ASCII bytes.
+    let [b, a] = unsafe { s.mut_bytes_at(&[3, 0]) }.unwrap();
+    *a = b'x';
+    *b = b'y';
+    assert_eq!("x\u{e9}y", s);
+
+    // Safety: Each call fails, so nothing is written.
+    unsafe {
+        let e = s.mut_bytes_at(&[0, 2]).unwrap_err();
+        assert_eq!(
+            MutElemsError::NotCharBoundary {
+                position: 1,
+                index: 2,
+            },
+            e,
+        );
+        assert_eq!(MutElemsErrorKind::CharBoundary, e.kind());
+        assert_eq!(
+            Some(MutElemsError::IndexBound {
+                position: 0,
+                index: 4,
+                length: 4,
+            }),
+            s.mut_bytes_at(&[4]).err(),
+        );
+        assert_eq!(
+            Some(MutElemsError::IndicesOverlap {
+                first: 0,
+                second: 1,
+                index: 1,
+            }),
+            s.mut_bytes_at(&[1, 1]).err(),
+        );
+    }
+}
+
+#[test]
+fn test_mut_elems_by() {
+    let mut v = [5u8, 0, 3, 0, 8];
+    let [a, b] = v
+        .mut_elems_by(|s| {
+            let first = s.iter().position(|&e| e == 0).unwrap_or(0);
+            let last = s.iter().rposition(|&e| e == 0).unwrap_or(0);
+            [first, last]
+        })
+        .unwrap();
+    *a = 1;
+    *b = 2;
+    assert_eq!([5, 1, 3, 2, 8], v);
+
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 5,
+            length: 5,
+        }),
+        v.mut_elems_by(|s| [s.len()]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 0,
+        }),
+        v.mut_elems_by(|_| [0, 0]),
+    );
+}
+
+#[test]
+fn test_mut_elems_transact() {
+    let mut v = [1u8, 2, 3, 4];
+    assert_eq!(
+        Ok(Ok(())),
+        v.mut_elems_transact(&[3, 0], |[a, b]| {
+            core::mem::swap(a, b);
+            Ok::<_, ()>(())
+        }),
+    );
+    assert_eq!([4, 2, 3, 1], v);
+
+    assert_eq!(
+        Ok(Err(2)),
+        v.mut_elems_transact(&[0, 1, 2], |es| {
+            for (i, e) in es.into_iter().enumerate() {
+                *e = 0;
+                if i == 2 {
+                    return Err(i);
+                }
+            }
+            Ok(())
+        }),
+    );
+    assert_eq!([4, 2, 3, 1], v);
+
+    let mut called = false;
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 1,
+        }),
+        v.mut_elems_transact(&[1, 1], |_| {
+            called = true;
+            Ok::<_, ()>(())
+        }),
+    );
+    assert!(!called);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_mut_elems_guard() {
+    use alloc::vec;
+    use std::sync::Mutex;
+
+    let lock = Mutex::new(vec![1u8, 2, 3]);
+    let mut guard = lock.lock().unwrap();
+    let [a, b] = guard.mut_elems_guard(&[0, 2]).unwrap();
+    *a += 10;
+    // The lock stays held while the references are live.
+    assert!(lock.try_lock().is_err());
+    *b += 10;
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 0,
+            index: 3,
+            length: 3,
+        }),
+        guard.mut_elems_guard(&[3]),
+    );
+    drop(guard);
+    assert_eq!(vec![11, 2, 13], *lock.lock().unwrap());
+}
+
+#[test]
+fn test_mut_k_smallest() {
+    let mut v = [5u8, 1, 4, 1, 3, 9, 0];
+    let [a, b, c] = v.mut_k_smallest().unwrap();
+    assert_eq!([0, 1, 1], [*a, *b, *c]);
+    // Ties are taken in index order.
+    *b = 10;
+    *c = 11;
+    *a = 12;
+    assert_eq!([5, 10, 4, 11, 3, 9, 12], v);
+
+    let [hi, next] = v.mut_k_largest().unwrap();
+    assert_eq!([12, 11], [*hi, *next]);
+    let all: [&mut u8; 7] = v.mut_k_largest().unwrap();
+    assert_eq!([12, 11, 10, 9, 5, 4, 3], all.map(|e| *e));
+
+    let mut w = [2u8, 2, 2];
+    let [x, y] = w.mut_k_largest().unwrap();
+    *x = 0;
+    *y = 1;
+    assert_eq!([0, 1, 2], w);
+
+    let [] = w.mut_k_smallest().unwrap();
+    assert_eq!(
+        Err(MutElemsError::LengthMismatch {
+            expected: 4,
+            actual: 3,
+        }),
+        w.mut_k_smallest::<4>(),
+    );
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_as_mut_elems_vec_indexed() {
+    use alloc::vec;
+
+    let mut v = vec![1u8, 2, 3, 4];
+    let es = v.as_mut_elems_vec_indexed(&[2, 0]).unwrap();
+    for e in es {
+        *e *= 10;
+    }
+    assert_eq!(vec![10, 2, 30, 4], v);
+
+    let mut a = [1u8, 2, 3];
+    let es = a[..].as_mut_elems_vec_indexed(&[]).unwrap();
+    assert!(es.is_empty());
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 2,
+            index: 1,
+        }),
+        a.as_mut_elems_vec_indexed(&[1, 0, 1]),
+    );
+    assert_eq!(
+        Err(MutElemsError::IndexBound {
+            position: 1,
+            index: 3,
+            length: 3,
+        }),
+        a.as_mut_elems_vec_indexed(&[0, 3]),
+    );
+}
+
+#[test]
+fn test_mut_elems_index_max() {
+    const MAX: usize = usize::MAX;
+    let bound = |position, length| MutElemsError::IndexBound {
+        position,
+        index: MAX,
+        length,
+    };
+
+    let mut short = [0u8; 8];
+    assert_eq!(Err(bound(0, 8)), short.mut_elems(&[MAX]));
+    assert_eq!(Err(bound(1, 8)), short.mut_elems(&[0, MAX]));
+    assert_eq!(Err(bound(0, 8)), short.mut_elems(&[MAX, MAX]));
+    assert_eq!(Err(bound(2, 8)), short.mut_elems(&[3, 1, MAX]));
+    assert_eq!(
+        Err(bound(5, 8)),
+        short.mut_elems(&[0, 1, 2, 3, 4, MAX, 6, 7])
+    );
+    assert_eq!(Err(bound(1, 8)), short.mut_elems_sorted(&[0, MAX]));
+    #[cfg(feature = "alloc")]
+    assert_eq!(
+        Err(bound(6, 8)),
+        short.mut_elems_slice(&[0, 1, 2, 3, 4, 5, MAX])
+    );
+    assert_eq!([0; 8], short);
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_mut_elems_index_max_long() {
+    use alloc::vec;
+
+    const MAX: usize = usize::MAX;
+    const LEN: usize = BITSET_LIMIT * 16;
+    let bound = |position| MutElemsError::IndexBound {
+        position,
+        index: MAX,
+        length: LEN,
+    };
+
+    let mut long = vec![0u8; LEN];
+    assert_eq!(Err(bound(1)), long.mut_elems(&[0, MAX]));
+    // Neighbouring indices that would otherwise be checked
+    // with a bitset over their span.
+    assert_eq!(Err(bound(4)), long.mut_elems(&[10, 11, 12, 13, MAX, 15]));
+    // Scattered indices, checked by sorting.
+    let scattered: [usize; 8] = core::array::from_fn(|i| i * BITSET_LIMIT);
+    let mut indices = scattered;
+    indices[3] = MAX;
+    assert_eq!(Err(bound(3)), long.mut_elems(&indices));
+    assert_eq!(Err(bound(3)), long.mut_elems_slice(&indices));
+    let mut many: Vec<usize> = (0..100).map(|i| i * 100).collect();
+    many[99] = MAX;
+    assert_eq!(Err(bound(99)), long.mut_elems_slice(&many));
+    assert!(long.iter().all(|&e| e == 0));
+}
+
+#[test]
+fn test_mut_elems_and_rest_ref() {
+    let mut v = [1u8, 2, 3, 4, 5];
+    let ([a, b], rest) = v.mut_elems_and_rest_ref(&[3, 0]).unwrap();
+    assert_eq!(5, rest.len());
+    assert!(!rest.is_empty());
+    assert!(rest.is_selected(0));
+    assert!(rest.is_selected(3));
+    assert!(!rest.is_selected(2));
+    assert_eq!(
+        [None, Some(&2), Some(&3), None, Some(&5), None],
+        [0, 1, 2, 3, 4, 5].map(|i| rest.get(i))
+    );
+    // References from the view can be held while the chosen
+    // elements are written.
+    let (x, y) = (&rest[1], &rest[4]);
+    *a = *x + *y;
+    *b = *x * *y;
+    assert_eq!([10, 2, 3, 7, 5], v);
+
+    let mut empty: [u8; 0] = [];
+    let ([], rest) = empty.mut_elems_and_rest_ref(&[]).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(
+        Err(MutElemsError::IndicesOverlap {
+            first: 0,
+            second: 1,
+            index: 2,
+        }),
+        v.mut_elems_and_rest_ref(&[2, 2]).map(|_| ()),
+    );
+}
+
+#[test]
+fn test_try_as_mut_elems() {
+    let mut a = [1u8, 2, 3];
+    let s: &mut [u8] = &mut a;
+    let [x, _, z] = s.try_as_mut_elems().unwrap();
+    core::mem::swap(x, z);
+    assert_eq!(
+        Err(MutElemsError::LengthMismatch {
+            expected: 2,
+            actual: 3,
+        }),
+        s.try_as_mut_elems::<2>(),
+    );
+    assert_eq!([3, 2, 1], a);
 }
@@ -0,0 +1,51 @@
+// Check that the validated hot paths cannot panic. The
+// `no_panic` attribute fails to link any function that might
+// panic, but only once optimizations have removed the checks
+// that cannot fail, so these are only built for release:
+//
+//     cargo test --release --test no-panic
+
+#![cfg(not(debug_assertions))]
+
+use mut_elems::*;
+use no_panic::no_panic;
+
+#[no_panic]
+fn mut_elems_2<'a>(
+    s: &'a mut [u64],
+    indices: &[usize; 2],
+) -> Result<[&'a mut u64; 2], MutElemsError> {
+    s.mut_elems(indices)
+}
+
+#[no_panic]
+fn mut_elems_16<'a>(
+    s: &'a mut [u64],
+    indices: &[usize; 16],
+) -> Result<[&'a mut u64; 16], MutElemsError> {
+    s.mut_elems(indices)
+}
+
+#[no_panic]
+fn as_mut_elems_8(a: &mut [u64; 8]) -> [&mut u64; 8] {
+    a.as_mut_elems()
+}
+
+#[no_panic]
+fn as_mut_elems_vec(s: &mut [u64]) -> Vec<&mut u64> {
+    s.as_mut_elems_vec()
+}
+
+#[test]
+fn test_no_panic() {
+    let mut v = vec![0u64; 8192];
+    assert!(mut_elems_2(&mut v, &[1, 0]).is_ok());
+    let indices = std::array::from_fn(|i| i * 3);
+    assert!(mut_elems_16(&mut v[..64], &indices).is_ok());
+    assert!(mut_elems_16(&mut v, &indices).is_ok());
+    let mut a = [0u64; 8];
+    let [e, ..] = as_mut_elems_8(&mut a);
+    *e = 1;
+    assert_eq!(1, a[0]);
+    assert_eq!(8192, as_mut_elems_vec(&mut v).len());
+}